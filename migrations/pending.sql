@@ -0,0 +1,192 @@
+CREATE TABLE devices_rotated_tokens(
+    refresh_token_hash TEXT PRIMARY KEY,
+    device_id UUID NOT NULL REFERENCES devices(id) ON DELETE CASCADE,
+    rotated_at BIGINT NOT NULL
+);
+
+ALTER TABLE devices ADD COLUMN refresh_token_hash TEXT UNIQUE;
+
+CREATE TABLE api_keys(
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    key_hash TEXT UNIQUE NOT NULL,
+    key_prefix TEXT NOT NULL,
+    scope TEXT NOT NULL,
+    expires_at BIGINT,
+    last_used_at BIGINT,
+    updated_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+ALTER TABLE users ADD COLUMN totp_secret TEXT;
+ALTER TABLE users ADD COLUMN totp_enabled BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN totp_last_used_step BIGINT;
+
+CREATE TABLE recovery_codes(
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash TEXT UNIQUE NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE TABLE passkeys(
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    credential_id TEXT UNIQUE NOT NULL,
+    name TEXT NOT NULL,
+    public_key TEXT NOT NULL,
+    algorithm BIGINT NOT NULL,
+    sign_count BIGINT NOT NULL,
+    last_used_at BIGINT,
+    updated_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE TABLE webauthn_challenges(
+    id UUID PRIMARY KEY,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    challenge TEXT NOT NULL,
+    ceremony TEXT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE TABLE user_identities(
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    provider TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    email TEXT,
+    updated_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL,
+    UNIQUE (provider, subject),
+    UNIQUE (user_id, provider)
+);
+
+INSERT INTO user_identities (id, user_id, provider, subject, email, updated_at, created_at)
+SELECT gen_random_uuid(), id, 'apple', id_apple, email, updated_at, created_at
+FROM users WHERE id_apple IS NOT NULL;
+
+ALTER TABLE users DROP COLUMN id_apple;
+
+CREATE TABLE magic_links(
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash TEXT UNIQUE NOT NULL,
+    expires_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE TABLE signin_attempts(
+    key TEXT PRIMARY KEY,
+    failures INTEGER NOT NULL,
+    locked_until BIGINT NOT NULL,
+    last_failed_at BIGINT NOT NULL,
+    alerted_at BIGINT
+);
+
+CREATE INDEX signin_attempts_last_failed_at_idx ON signin_attempts(last_failed_at);
+
+CREATE TABLE rate_limit_buckets(
+    key TEXT PRIMARY KEY,
+    tokens DOUBLE PRECISION NOT NULL,
+    allowed BOOLEAN NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+ALTER TABLE users ADD COLUMN username_updated_at BIGINT;
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE users
+    ADD COLUMN private BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN discoverable_by_username BOOLEAN NOT NULL DEFAULT true,
+    ADD COLUMN discoverable_by_email BOOLEAN NOT NULL DEFAULT false;
+
+CREATE INDEX users_username_key_trgm_idx ON users USING GIN (username_key gin_trgm_ops);
+CREATE INDEX users_displayname_trgm_idx ON users USING GIN (lower(displayname) gin_trgm_ops);
+
+CREATE TABLE blocks(
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    blocked_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at BIGINT NOT NULL,
+    PRIMARY KEY (user_id, blocked_user_id)
+);
+
+CREATE INDEX blocks_blocked_user_id_idx ON blocks(blocked_user_id);
+
+CREATE TABLE user_settings(
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    reminder_lead_time INTEGER NOT NULL,
+    quiet_hours_start SMALLINT,
+    quiet_hours_end SMALLINT,
+    timezone TEXT NOT NULL,
+    default_memo_priority SMALLINT NOT NULL,
+    default_memo_visibility SMALLINT NOT NULL,
+    locale TEXT NOT NULL,
+    week_start SMALLINT NOT NULL,
+    notification_channels TEXT[] NOT NULL,
+    updated_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+ALTER TABLE user_settings ADD COLUMN quiet_hours_bypass BOOLEAN NOT NULL DEFAULT true;
+
+ALTER TABLE memos ADD COLUMN notified_trigger_at BIGINT;
+UPDATE memos SET notified_trigger_at = trigger_at
+WHERE trigger_at <= (EXTRACT(EPOCH FROM now()) * 1000)::BIGINT;
+
+CREATE TABLE deferred_notifications(
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    memo_id UUID NOT NULL REFERENCES memos(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    deliver_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX deferred_notifications_deliver_at_idx ON deferred_notifications(deliver_at);
+
+ALTER TABLE memos ADD COLUMN notification_channels TEXT[];
+ALTER TABLE deferred_notifications ADD COLUMN notification_channels TEXT[] NOT NULL DEFAULT '{push}';
+
+ALTER TABLE user_settings
+    ADD COLUMN digest_enabled BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN digest_time SMALLINT NOT NULL DEFAULT 480,
+    ADD COLUMN digest_sent_at BIGINT,
+    ADD COLUMN digest_leased_until BIGINT;
+
+CREATE INDEX user_settings_digest_idx ON user_settings(digest_sent_at) WHERE digest_enabled = true;
+
+CREATE TABLE webhooks(
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT[] NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    updated_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX webhooks_user_id_idx ON webhooks(user_id);
+
+CREATE TABLE webhook_deliveries(
+    id UUID PRIMARY KEY,
+    webhook_id UUID NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    event TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    response_status INTEGER,
+    error TEXT,
+    next_attempt_at BIGINT,
+    updated_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX webhook_deliveries_webhook_id_created_at_idx ON webhook_deliveries(webhook_id, created_at DESC);
+CREATE INDEX webhook_deliveries_status_next_attempt_at_idx ON webhook_deliveries(status, next_attempt_at);
+CREATE INDEX webhook_deliveries_created_at_idx ON webhook_deliveries(created_at);
@@ -0,0 +1,45 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use validator::Validate;
+
+use crate::{
+    app::models::{api_error::ApiError, app_state::AppState},
+    auth::{enums::scope::Scope, models::access_token_claims::ExtractClaims},
+};
+
+use super::{dtos::create_api_key_dto::CreateApiKeyDto, models::api_key::ApiKey, service};
+
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+    Json(dto): Json<CreateApiKeyDto>,
+) -> Result<Json<ApiKey>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    dto.validate()?;
+    match service::create_api_key(&dto, &claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_api_keys(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<Json<Vec<ApiKey>>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    match service::get_api_keys(&claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn delete_api_key(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<(), ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    service::delete_api_key(&id, &claims, &state).await
+}
@@ -0,0 +1,15 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateApiKeyDto {
+    #[validate(length(
+        min = 1,
+        max = 64,
+        message = "name must be between 1 and 64 characters."
+    ))]
+    pub name: String,
+    #[validate(custom = "super::validate_scopes")]
+    pub scopes: Vec<String>,
+    pub expires_at: Option<i64>,
+}
@@ -0,0 +1,22 @@
+use std::borrow::Cow;
+
+use validator::ValidationError;
+
+use crate::auth::enums::scope::Scope;
+
+pub mod create_api_key_dto;
+
+pub fn validate_scopes(value: &[String]) -> Result<(), ValidationError> {
+    let allowed = [Scope::MEMOS_READ, Scope::MEMOS_WRITE];
+
+    match !value.is_empty() && value.iter().all(|scope| allowed.contains(&scope.as_str())) {
+        true => Ok(()),
+        false => {
+            let mut error = ValidationError::new("invalid_scopes");
+            error.message = Some(Cow::from(
+                "scopes must be one or more of memos:read and memos:write.",
+            ));
+            Err(error)
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod controller;
+pub mod dtos;
+pub mod models;
+pub mod service;
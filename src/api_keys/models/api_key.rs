@@ -0,0 +1,71 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    api_keys::dtos::create_api_key_dto::CreateApiKeyDto,
+    app,
+    auth::{
+        enums::{secret_token_domain::SecretTokenDomain, token_type::TokenType},
+        models::access_token_claims::AccessTokenClaims,
+        util::{api_key, secret_token},
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ApiKey {
+    pub id: sqlx::types::Uuid,
+    pub user_id: sqlx::types::Uuid,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[sqlx(skip)]
+    pub key: Option<String>,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub key_prefix: String,
+    pub scope: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<i64>,
+    pub updated_at: i64,
+    pub created_at: i64,
+}
+
+impl ApiKey {
+    pub fn new(dto: &CreateApiKeyDto, user_id: &str, api_key_secret: &str) -> Self {
+        let current_time = app::util::time::current_time_in_millis();
+        let key = api_key::new();
+
+        Self {
+            id: Uuid::new_v4(),
+            user_id: Uuid::from_str(user_id).unwrap(),
+            name: dto.name.trim().to_string(),
+            key_hash: secret_token::hash(&key, api_key_secret, SecretTokenDomain::API_KEY),
+            key_prefix: key.chars().take(api_key::PREFIX.len() + 6).collect(),
+            key: Some(key),
+            scope: dto.scopes.join(" "),
+            expires_at: dto.expires_at,
+            last_used_at: None,
+            updated_at: current_time,
+            created_at: current_time,
+        }
+    }
+
+    pub fn to_claims(&self) -> AccessTokenClaims {
+        AccessTokenClaims {
+            sub: self.user_id.to_string(),
+            sid: None,
+            typ: TokenType::API_KEY.to_string(),
+            scope: self.scope.to_string(),
+            jti: self.id.to_string(),
+            iat: (self.created_at / 1000) as u64,
+            // keys without an expiry never expire
+            exp: self
+                .expires_at
+                .map_or(u64::MAX, |expires_at| (expires_at / 1000) as u64),
+        }
+    }
+}
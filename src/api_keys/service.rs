@@ -0,0 +1,206 @@
+use std::str::FromStr;
+
+use axum::http::StatusCode;
+use sqlx::{types::Uuid, Postgres};
+
+use crate::{
+    app::{
+        models::{api_error::ApiError, app_state::AppState},
+        util::time,
+    },
+    auth::{
+        enums::secret_token_domain::SecretTokenDomain,
+        models::access_token_claims::AccessTokenClaims, util::secret_token,
+    },
+};
+
+use super::{dtos::create_api_key_dto::CreateApiKeyDto, models::api_key::ApiKey};
+
+pub async fn create_api_key(
+    dto: &CreateApiKeyDto,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<ApiKey, ApiError> {
+    if let Some(expires_at) = dto.expires_at {
+        if expires_at <= time::current_time_in_millis() {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "expires_at must be in the future.",
+            ));
+        }
+    }
+
+    let api_key = ApiKey::new(dto, &claims.sub, &state.envy.api_key_secret);
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO api_keys (
+            id, user_id, name, key_hash, key_prefix, scope,
+            expires_at, last_used_at, updated_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        ",
+    )
+    .bind(api_key.id)
+    .bind(api_key.user_id)
+    .bind(&api_key.name)
+    .bind(&api_key.key_hash)
+    .bind(&api_key.key_prefix)
+    .bind(&api_key.scope)
+    .bind(api_key.expires_at)
+    .bind(api_key.last_used_at)
+    .bind(api_key.updated_at)
+    .bind(api_key.created_at)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(api_key),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to create api key.",
+            ))
+        }
+    }
+}
+
+pub async fn get_api_keys(
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<Vec<ApiKey>, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, ApiKey>(
+        "
+        SELECT * FROM api_keys
+        WHERE user_id = $1
+        ORDER BY created_at DESC
+        ",
+    )
+    .bind(Uuid::from_str(&claims.sub).unwrap_or_default())
+    .fetch_all(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(api_keys) => Ok(api_keys),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get api keys.",
+            ))
+        }
+    }
+}
+
+pub async fn use_api_key(key: &str, state: &AppState) -> Result<ApiKey, ApiError> {
+    let current_time = time::current_time_in_millis();
+
+    let sqlx_result = sqlx::query_as::<Postgres, ApiKey>(
+        "
+        UPDATE api_keys SET last_used_at = $1
+        WHERE key_hash = $2 AND (expires_at IS NULL OR expires_at > $1)
+        RETURNING *
+        ",
+    )
+    .bind(current_time)
+    .bind(secret_token::hash(
+        key,
+        &state.envy.api_key_secret,
+        SecretTokenDomain::API_KEY,
+    ))
+    .fetch_optional(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(api_key) => match api_key {
+            Some(api_key) => Ok(api_key),
+            None => Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "Invalid or expired api key.",
+            )),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get api key.",
+            ))
+        }
+    }
+}
+
+pub async fn delete_api_key(
+    id: &str,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let Ok(id) = Uuid::from_str(id) else {
+        return Err(ApiError::new(StatusCode::NOT_FOUND, "Api key not found."));
+    };
+
+    let sqlx_result = sqlx::query(
+        "
+        DELETE FROM api_keys
+        WHERE id = $1 AND user_id = $2
+        ",
+    )
+    .bind(id)
+    .bind(Uuid::from_str(&claims.sub).unwrap_or_default())
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(ApiError::new(StatusCode::NOT_FOUND, "Api key not found.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to delete api key.",
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    use crate::app::test_util;
+
+    use super::*;
+
+    #[sqlx::test(migrations = false)]
+    async fn creates_lists_and_revokes_api_key(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let claims = test_util::access_claims(&test_util::insert_user("someone", &state).await);
+        let dto: CreateApiKeyDto =
+            serde_json::from_value(json!({ "name": "Backup", "scopes": ["memos:read"] })).unwrap();
+
+        let api_key = create_api_key(&dto, &claims, &state).await.unwrap();
+        let key = api_key.key.unwrap();
+
+        let api_keys = get_api_keys(&claims, &state).await.unwrap();
+        assert_eq!(api_keys.len(), 1);
+        assert_eq!(api_keys[0].id, api_key.id);
+        assert_eq!(api_keys[0].to_claims().exp, u64::MAX);
+        assert_eq!(use_api_key(&key, &state).await.unwrap().id, api_key.id);
+
+        let e = delete_api_key("not-a-uuid", &claims, &state)
+            .await
+            .unwrap_err();
+        assert_eq!(e.code, StatusCode::NOT_FOUND);
+
+        delete_api_key(&api_key.id.to_string(), &claims, &state)
+            .await
+            .unwrap();
+        assert!(get_api_keys(&claims, &state).await.unwrap().is_empty());
+
+        let e = use_api_key(&key, &state).await.unwrap_err();
+        assert_eq!(e.code, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -1,2 +1,3 @@
-pub static APP_NAME: &'static str = "Perroquet";
-pub static FRONTEND_URL: &'static str = "https://perroquet.beamcove.com";
+pub static APP_NAME: &str = "Perroquet";
+pub static FRONTEND_URL: &str = "https://perroquet.beamcove.com";
+pub static WEBAUTHN_RP_ID: &str = "perroquet.beamcove.com";
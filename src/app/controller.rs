@@ -1,7 +1,10 @@
 use axum::{extract::State, Json};
 use validator::Validate;
 
-use crate::{auth::models::access_token_claims::ExtractClaims, AppState};
+use crate::{
+    auth::{enums::scope::Scope, models::access_token_claims::ExtractClaims},
+    AppState,
+};
 
 use super::{
     dtos::sync_dto::SyncDto,
@@ -18,6 +21,8 @@ pub async fn sync(
     ExtractClaims(claims): ExtractClaims,
     Json(dto): Json<SyncDto>,
 ) -> Result<Json<SyncData>, ApiError> {
+    claims.require_scope(Scope::USERS_READ)?;
+    claims.require_scope(Scope::MEMOS_READ)?;
     dto.validate()?;
     match service::sync(&dto, &claims, &state).await {
         Ok(data) => Ok(Json(data)),
@@ -6,5 +6,7 @@ use crate::memos::dtos::get_memos_dto::GetMemosDto;
 #[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct SyncDto {
     pub user: Option<bool>,
+    pub settings: Option<bool>,
+    #[validate]
     pub memos: Option<GetMemosDto>,
 }
@@ -3,17 +3,40 @@ use serde::Deserialize;
 #[derive(Debug, Clone, Deserialize)]
 pub struct Envy {
     pub app_env: String,
+    pub api_url: String,
     pub port: Option<u16>,
+    pub trust_proxy: Option<bool>,
+    pub rate_limit_backend: Option<String>,
+    pub rate_limits: Option<String>,
+    pub storage_backend: Option<String>,
+    pub storage_path: Option<String>,
+    pub storage_public_url: Option<String>,
 
     pub database_url: String,
 
     pub jwt_secret: String,
+    pub jwt_keys: Option<String>,
+    pub refresh_token_secret: String,
+    pub api_key_secret: String,
+
+    pub password_min_length: Option<usize>,
+    pub password_min_score: Option<u8>,
+    pub breached_passwords_path: Option<String>,
+    pub argon2_memory_cost: Option<u32>,
+    pub argon2_time_cost: Option<u32>,
+    pub argon2_parallelism: Option<u32>,
 
     pub apple_team_id: String,
     pub apple_client_id: String,
     pub apple_key_id: String,
     pub apple_private_key: String,
 
+    pub google_client_id_ios: String,
+    pub google_client_id_android: String,
+    pub google_client_id_web: String,
+
+    pub oidc_providers: Option<String>,
+
     pub fcm_project_name: String,
     pub fcm_client_email: String,
     pub fcm_private_key: String,
@@ -86,33 +86,56 @@ impl FcmClient {
         message: FcmMessage,
         http_client: &reqwest::Client,
     ) -> Result<(), String> {
-        let click_action = match message.click_action {
-            Some(click_action) => click_action,
-            None => "none".to_string(),
-        };
-
-        let fcm_message = json!({
-            "message": {
+        let mut fcm_message = match (&message.title, &message.body) {
+            (None, None) => json!({
                 "token": message.token,
-                "notification": {
-                    "title": message.title,
-                    "body": message.body,
-                },
                 "android": {
-                    "notification": {
-                        "click_action": click_action
-                    }
+                    "priority": "normal"
                 },
                 "apns": {
+                    "headers": {
+                        "apns-push-type": "background",
+                        "apns-priority": "5"
+                    },
                     "payload": {
                         "aps": {
-                            "sound": "default",
-                            "category": click_action
+                            "content-available": 1
                         }
                     }
                 }
+            }),
+            _ => {
+                let click_action = match &message.click_action {
+                    Some(click_action) => click_action.to_string(),
+                    None => "none".to_string(),
+                };
+
+                json!({
+                    "token": message.token,
+                    "notification": {
+                        "title": message.title.as_deref().unwrap_or_default(),
+                        "body": message.body.as_deref().unwrap_or_default(),
+                    },
+                    "android": {
+                        "notification": {
+                            "click_action": click_action
+                        }
+                    },
+                    "apns": {
+                        "payload": {
+                            "aps": {
+                                "sound": "default",
+                                "category": click_action
+                            }
+                        }
+                    }
+                })
             }
-        });
+        };
+        if let Some(data) = &message.data {
+            fcm_message["data"] = json!(data);
+        }
+        let fcm_message = json!({ "message": fcm_message });
 
         let Ok(payload) = serde_json::to_vec(&fcm_message) else {
             return Err(message.token);
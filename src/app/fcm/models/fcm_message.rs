@@ -1,9 +1,13 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+// a message without a title and body is sent as a silent data-only message
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FcmMessage {
     pub token: String,
-    pub title: String,
-    pub body: String,
+    pub title: Option<String>,
+    pub body: Option<String>,
     pub click_action: Option<String>,
+    pub data: Option<HashMap<String, String>>,
 }
@@ -4,5 +4,9 @@ pub mod dtos;
 pub mod envy;
 pub mod fcm;
 pub mod models;
+pub mod rate_limit;
 pub mod service;
+pub mod storage;
+#[cfg(test)]
+pub mod test_util;
 pub mod util;
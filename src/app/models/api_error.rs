@@ -1,6 +1,11 @@
 use std::borrow::Cow;
 
-use axum::{extract::rejection::JsonRejection, http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    extract::rejection::JsonRejection,
+    http::{header::RETRY_AFTER, HeaderMap, HeaderValue, StatusCode},
+    response::IntoResponse,
+    Json,
+};
 use serde_json::json;
 use validator::{ValidationError, ValidationErrors};
 
@@ -8,6 +13,8 @@ use validator::{ValidationError, ValidationErrors};
 pub struct ApiError {
     pub code: StatusCode,
     pub message: String,
+    // boxed so every Result carrying an ApiError stays small
+    pub headers: Box<HeaderMap>,
 }
 
 impl ApiError {
@@ -15,6 +22,7 @@ impl ApiError {
         Self {
             code,
             message: message.to_string(),
+            headers: Box::default(),
         }
     }
 
@@ -22,8 +30,18 @@ impl ApiError {
         Self {
             code: StatusCode::INTERNAL_SERVER_ERROR,
             message: "An error occurred.".to_string(),
+            headers: Box::default(),
         }
     }
+
+    pub fn too_many_requests(message: &str, retry_after_secs: u64) -> Self {
+        let mut error = Self::new(StatusCode::TOO_MANY_REQUESTS, message);
+        error
+            .headers
+            .insert(RETRY_AFTER, HeaderValue::from(retry_after_secs));
+
+        error
+    }
 }
 
 impl From<JsonRejection> for ApiError {
@@ -38,6 +56,7 @@ impl From<JsonRejection> for ApiError {
         Self {
             code,
             message: rejection.to_string(),
+            headers: Box::default(),
         }
     }
 }
@@ -52,6 +71,7 @@ impl From<ValidationError> for ApiError {
         Self {
             code: StatusCode::BAD_REQUEST,
             message,
+            headers: Box::default(),
         }
     }
 }
@@ -72,6 +92,7 @@ impl From<ValidationErrors> for ApiError {
         Self {
             code: StatusCode::UNPROCESSABLE_ENTITY,
             message,
+            headers: Box::default(),
         }
     }
 }
@@ -83,6 +104,6 @@ impl IntoResponse for ApiError {
             "message": self.message,
         });
 
-        (self.code, Json(payload)).into_response()
+        (self.code, *self.headers, Json(payload)).into_response()
     }
 }
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use axum::{
     async_trait,
     extract::{FromRef, FromRequestParts},
@@ -5,7 +7,11 @@ use axum::{
 };
 use sqlx::PgPool;
 
-use crate::{app::envy::Envy, auth::authman::AuthMan};
+use crate::{
+    app::{envy::Envy, rate_limit::limiter::RateLimiter, storage::backend::StorageBackend},
+    auth::authman::AuthMan,
+    realtime::hub::RealtimeHub,
+};
 
 use super::api_error::ApiError;
 
@@ -15,6 +21,9 @@ pub struct AppState {
     pub http_client: reqwest::Client,
     pub authman: AuthMan,
     pub pool: PgPool,
+    pub rate_limiter: RateLimiter,
+    pub storage: Arc<dyn StorageBackend>,
+    pub realtime: RealtimeHub,
 }
 
 #[async_trait]
@@ -0,0 +1,42 @@
+use std::net::SocketAddr;
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRef, FromRequestParts},
+    http::request::Parts,
+    RequestPartsExt,
+};
+
+use super::{api_error::ApiError, app_state::AppState};
+
+pub struct ClientIp(pub String);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientIp
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let state = parts.extract_with_state::<AppState, _>(state).await?;
+
+        // the last hop is the one appended by our own proxy
+        if state.envy.trust_proxy.unwrap_or(false) {
+            let forwarded_for = parts
+                .headers
+                .get("x-forwarded-for")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.rsplit(',').next());
+            if let Some(forwarded_for) = forwarded_for {
+                return Ok(ClientIp(forwarded_for.trim().to_string()));
+            }
+        }
+
+        match parts.extensions.get::<ConnectInfo<SocketAddr>>() {
+            Some(ConnectInfo(addr)) => Ok(ClientIp(addr.ip().to_string())),
+            None => Err(ApiError::internal_server_error()),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod api_error;
 pub mod app_error;
 pub mod app_state;
+pub mod client_ip;
 pub mod sync_data;
@@ -0,0 +1,16 @@
+use std::fmt::Debug;
+
+use axum::async_trait;
+
+use crate::app::models::app_error::AppError;
+
+use super::models::{rate_limit_decision::RateLimitDecision, rate_limit_policy::RateLimitPolicy};
+
+#[async_trait]
+pub trait RateLimitBackend: Debug + Send + Sync {
+    async fn take(
+        &self,
+        key: &str,
+        policy: &RateLimitPolicy,
+    ) -> Result<RateLimitDecision, AppError>;
+}
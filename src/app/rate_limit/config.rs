@@ -0,0 +1,74 @@
+use super::{enums::rate_limit_key::RateLimitKey, models::rate_limit_policy::RateLimitPolicy};
+
+pub fn default_policies() -> Vec<RateLimitPolicy> {
+    vec![
+        RateLimitPolicy::new(None, None, 120, 60, RateLimitKey::USER),
+        RateLimitPolicy::new(
+            Some("POST"),
+            Some("/v1/auth/signup"),
+            5,
+            3600,
+            RateLimitKey::IP,
+        ),
+        RateLimitPolicy::new(
+            Some("POST"),
+            Some("/v1/auth/signin"),
+            20,
+            60,
+            RateLimitKey::IP,
+        )
+        .fail_closed(),
+        RateLimitPolicy::new(
+            Some("POST"),
+            Some("/v1/auth/signin/totp"),
+            20,
+            60,
+            RateLimitKey::IP,
+        )
+        .fail_closed(),
+        RateLimitPolicy::new(
+            Some("POST"),
+            Some("/v1/auth/signin/magic-link"),
+            20,
+            60,
+            RateLimitKey::IP,
+        )
+        .fail_closed(),
+        RateLimitPolicy::new(
+            Some("POST"),
+            Some("/v1/auth/password"),
+            5,
+            3600,
+            RateLimitKey::IP,
+        ),
+        RateLimitPolicy::new(
+            Some("POST"),
+            Some("/v1/auth/email"),
+            5,
+            3600,
+            RateLimitKey::USER,
+        ),
+        RateLimitPolicy::new(
+            Some("POST"),
+            Some("/v1/auth/magic-link"),
+            5,
+            3600,
+            RateLimitKey::IP,
+        )
+        .fail_closed(),
+        RateLimitPolicy::new(
+            Some("GET"),
+            Some("/v1/users/username-availability"),
+            30,
+            60,
+            RateLimitKey::IP,
+        ),
+        RateLimitPolicy::new(
+            Some("PUT"),
+            Some("/v1/users/me/avatar"),
+            10,
+            3600,
+            RateLimitKey::USER,
+        ),
+    ]
+}
@@ -0,0 +1,8 @@
+#[non_exhaustive]
+pub struct RateLimitKey;
+
+impl RateLimitKey {
+    pub const IP: &'static str = "ip";
+    pub const USER: &'static str = "user";
+    pub const ROUTE: &'static str = "route";
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use crate::app::models::app_error::AppError;
+
+use super::{
+    backend::RateLimitBackend,
+    models::{rate_limit_decision::RateLimitDecision, rate_limit_policy::RateLimitPolicy},
+};
+
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    backend: Arc<dyn RateLimitBackend>,
+    policies: Arc<Vec<RateLimitPolicy>>,
+}
+
+impl RateLimiter {
+    pub fn new(backend: Arc<dyn RateLimitBackend>, policies: Vec<RateLimitPolicy>) -> Self {
+        Self {
+            backend,
+            policies: Arc::new(policies),
+        }
+    }
+
+    // the most specific policy wins, later policies override earlier ones
+    pub fn policy(&self, method: &str, route: &str) -> Option<&RateLimitPolicy> {
+        self.policies
+            .iter()
+            .filter(|policy| policy.matches(method, route))
+            .max_by_key(|policy| (policy.route.is_some(), policy.method.is_some()))
+    }
+
+    pub async fn take(
+        &self,
+        key: &str,
+        policy: &RateLimitPolicy,
+    ) -> Result<RateLimitDecision, AppError> {
+        self.backend.take(key, policy).await
+    }
+}
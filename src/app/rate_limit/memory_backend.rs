@@ -0,0 +1,135 @@
+use std::collections::{BTreeSet, HashMap};
+
+use axum::async_trait;
+use tokio::sync::Mutex;
+
+use crate::app::{models::app_error::AppError, util::time};
+
+use super::{
+    backend::RateLimitBackend,
+    models::{rate_limit_decision::RateLimitDecision, rate_limit_policy::RateLimitPolicy},
+};
+
+static MAX_BUCKETS: usize = 100000;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: i64,
+    full_at: i64,
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    buckets: HashMap<String, Bucket>,
+    // (updated_at, key) from the least to the most recently used bucket
+    recency: BTreeSet<(i64, String)>,
+}
+
+impl Buckets {
+    // a full bucket is the same as a missing one, so those at the front go first,
+    // then the least recently used one when there is no room left
+    fn evict(&mut self, current_time: i64) {
+        while let Some((updated_at, key)) = self.recency.first().cloned() {
+            let full = match self.buckets.get(&key) {
+                Some(bucket) => bucket.full_at <= current_time,
+                None => true,
+            };
+            if !full && self.buckets.len() < MAX_BUCKETS {
+                break;
+            }
+
+            self.recency.remove(&(updated_at, key.clone()));
+            self.buckets.remove(&key);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    buckets: Mutex<Buckets>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RateLimitBackend for MemoryBackend {
+    async fn take(
+        &self,
+        key: &str,
+        policy: &RateLimitPolicy,
+    ) -> Result<RateLimitDecision, AppError> {
+        let current_time = time::current_time_in_millis();
+        let capacity = policy.capacity as f64;
+        let refill_per_milli = policy.refill_per_milli();
+
+        let mut buckets = self.buckets.lock().await;
+        buckets.evict(current_time);
+
+        let Buckets { buckets, recency } = &mut *buckets;
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: current_time,
+            full_at: current_time,
+        });
+        recency.remove(&(bucket.updated_at, key.to_string()));
+
+        let elapsed = (current_time - bucket.updated_at).max(0) as f64;
+        let mut tokens = (bucket.tokens + elapsed * refill_per_milli).min(capacity);
+        let allowed = tokens >= 1.0;
+        if allowed {
+            tokens -= 1.0;
+        }
+
+        bucket.tokens = tokens;
+        bucket.updated_at = current_time;
+        bucket.full_at = current_time + ((capacity - tokens) / refill_per_milli).ceil() as i64;
+        recency.insert((current_time, key.to_string()));
+
+        Ok(RateLimitDecision::new(allowed, tokens, policy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::rate_limit::enums::rate_limit_key::RateLimitKey;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn evicts_the_least_recently_used_bucket_when_full() {
+        let backend = MemoryBackend::new();
+        let policy = RateLimitPolicy::new(None, None, 1, 3600, RateLimitKey::IP);
+
+        for i in 0..MAX_BUCKETS {
+            backend.take(&format!("ip:{}", i), &policy).await.unwrap();
+        }
+        backend.take("ip:1", &policy).await.unwrap();
+        backend.take("ip:new", &policy).await.unwrap();
+
+        let buckets = backend.buckets.lock().await;
+        assert_eq!(buckets.buckets.len(), MAX_BUCKETS);
+        assert_eq!(buckets.recency.len(), MAX_BUCKETS);
+        assert!(!buckets.buckets.contains_key("ip:0"));
+        assert!(buckets.buckets.contains_key("ip:1"));
+        assert!(buckets.buckets.contains_key("ip:new"));
+    }
+
+    #[tokio::test]
+    async fn evicts_full_buckets_first() {
+        let backend = MemoryBackend::new();
+        let policy = RateLimitPolicy::new(None, None, 1000, 1, RateLimitKey::IP);
+
+        backend.take("ip:0", &policy).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        backend.take("ip:1", &policy).await.unwrap();
+
+        let buckets = backend.buckets.lock().await;
+        assert!(!buckets.buckets.contains_key("ip:0"));
+        assert!(buckets.buckets.contains_key("ip:1"));
+    }
+}
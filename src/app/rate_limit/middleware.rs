@@ -0,0 +1,102 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    app::models::{api_error::ApiError, app_state::AppState, client_ip::ClientIp},
+    auth::{models::access_token_claims::AccessTokenClaims, util::api_key},
+};
+
+use super::{enums::rate_limit_key::RateLimitKey, models::rate_limit_decision::RateLimitDecision};
+
+pub async fn rate_limit(
+    State(state): State<AppState>,
+    ClientIp(client_ip): ClientIp,
+    matched_path: Option<MatchedPath>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let route = match matched_path {
+        Some(matched_path) => matched_path.as_str().to_string(),
+        None => request.uri().path().to_string(),
+    };
+
+    let Some(policy) = state.rate_limiter.policy(&method, &route) else {
+        return next.run(request).await;
+    };
+
+    let subject = match policy.key_by.as_str() {
+        RateLimitKey::ROUTE => "*".to_string(),
+        RateLimitKey::USER => match user_subject(request.headers(), &state) {
+            Some(subject) => subject,
+            None => format!("ip:{}", client_ip),
+        },
+        _ => format!("ip:{}", client_ip),
+    };
+    let key = format!(
+        "{} {}:{}",
+        policy.method.as_deref().unwrap_or("*"),
+        policy.route.as_deref().unwrap_or("*"),
+        subject
+    );
+
+    let decision = match state.rate_limiter.take(&key, policy).await {
+        Ok(decision) => decision,
+        Err(e) => {
+            tracing::error!("rate limit for {} failed: {}", key, e.message);
+            if policy.fail_closed {
+                return ApiError::new(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "Service unavailable. Try again later.",
+                )
+                .into_response();
+            }
+            return next.run(request).await;
+        }
+    };
+
+    if !decision.allowed {
+        let mut error = ApiError::too_many_requests(
+            "Too many requests. Try again later.",
+            decision.retry_after_secs,
+        );
+        insert_rate_limit_headers(&mut error.headers, &decision);
+        return error.into_response();
+    }
+
+    let mut response = next.run(request).await;
+    insert_rate_limit_headers(response.headers_mut(), &decision);
+
+    response
+}
+
+// api keys are not verified here, so they share the bucket of their ip
+fn user_subject(headers: &HeaderMap, state: &AppState) -> Option<String> {
+    let bearer = AccessTokenClaims::bearer_from_headers(headers).ok()?;
+    if api_key::is_api_key(bearer) {
+        return None;
+    }
+
+    let claims = AccessTokenClaims::from_jwt(bearer, state.authman.keyring(), None, true).ok()?;
+
+    Some(format!("user:{}", claims.sub))
+}
+
+fn insert_rate_limit_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    headers.insert(
+        HeaderName::from_static("ratelimit-limit"),
+        HeaderValue::from(decision.limit),
+    );
+    headers.insert(
+        HeaderName::from_static("ratelimit-remaining"),
+        HeaderValue::from(decision.remaining),
+    );
+    headers.insert(
+        HeaderName::from_static("ratelimit-reset"),
+        HeaderValue::from(decision.reset_secs),
+    );
+}
@@ -0,0 +1,8 @@
+pub mod backend;
+pub mod config;
+pub mod enums;
+pub mod limiter;
+pub mod memory_backend;
+pub mod middleware;
+pub mod models;
+pub mod postgres_backend;
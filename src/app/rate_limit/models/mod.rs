@@ -0,0 +1,2 @@
+pub mod rate_limit_decision;
+pub mod rate_limit_policy;
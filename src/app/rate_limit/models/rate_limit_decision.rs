@@ -0,0 +1,26 @@
+use super::rate_limit_policy::RateLimitPolicy;
+
+#[derive(Debug, Clone)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset_secs: u64,
+    pub retry_after_secs: u64,
+}
+
+impl RateLimitDecision {
+    pub fn new(allowed: bool, tokens: f64, policy: &RateLimitPolicy) -> Self {
+        let refill_per_milli = policy.refill_per_milli();
+        let tokens = tokens.max(0.0);
+
+        Self {
+            allowed,
+            limit: policy.capacity,
+            remaining: tokens.floor() as u32,
+            reset_secs: ((policy.capacity as f64 - tokens) / refill_per_milli / 1000.0).ceil()
+                as u64,
+            retry_after_secs: ((1.0 - tokens).max(0.0) / refill_per_milli / 1000.0).ceil() as u64,
+        }
+    }
+}
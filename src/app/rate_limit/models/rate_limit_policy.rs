@@ -0,0 +1,54 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitPolicy {
+    pub method: Option<String>,
+    pub route: Option<String>,
+    pub capacity: u32,
+    pub period_secs: u64,
+    pub key_by: String,
+    // rejects requests instead of letting them through when the backend fails
+    #[serde(default)]
+    pub fail_closed: bool,
+}
+
+impl RateLimitPolicy {
+    pub fn new(
+        method: Option<&str>,
+        route: Option<&str>,
+        capacity: u32,
+        period_secs: u64,
+        key_by: &str,
+    ) -> Self {
+        Self {
+            method: method.map(|method| method.to_string()),
+            route: route.map(|route| route.to_string()),
+            capacity,
+            period_secs,
+            key_by: key_by.to_string(),
+            fail_closed: false,
+        }
+    }
+
+    pub fn fail_closed(mut self) -> Self {
+        self.fail_closed = true;
+        self
+    }
+
+    pub fn matches(&self, method: &str, route: &str) -> bool {
+        let method_matches = match &self.method {
+            Some(policy_method) => policy_method.eq_ignore_ascii_case(method),
+            None => true,
+        };
+        let route_matches = match &self.route {
+            Some(policy_route) => policy_route == route,
+            None => true,
+        };
+
+        method_matches && route_matches
+    }
+
+    pub fn refill_per_milli(&self) -> f64 {
+        self.capacity as f64 / (self.period_secs.max(1) * 1000) as f64
+    }
+}
@@ -0,0 +1,72 @@
+use axum::async_trait;
+use rand::Rng;
+use sqlx::{PgPool, Postgres};
+
+use crate::app::{models::app_error::AppError, util::time};
+
+use super::{
+    backend::RateLimitBackend,
+    models::{rate_limit_decision::RateLimitDecision, rate_limit_policy::RateLimitPolicy},
+};
+
+static STALE_BUCKET_EXP: i64 = 86400000;
+
+#[derive(Debug)]
+pub struct PostgresBackend {
+    pool: PgPool,
+}
+
+impl PostgresBackend {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RateLimitBackend for PostgresBackend {
+    async fn take(
+        &self,
+        key: &str,
+        policy: &RateLimitPolicy,
+    ) -> Result<RateLimitDecision, AppError> {
+        let current_time = time::current_time_in_millis();
+
+        if rand::thread_rng().gen_ratio(1, 1000) {
+            let _ = sqlx::query("DELETE FROM rate_limit_buckets WHERE updated_at < $1")
+                .bind(current_time - STALE_BUCKET_EXP)
+                .execute(&self.pool)
+                .await;
+        }
+
+        // every SET expression sees the bucket as it was before this request
+        let sqlx_result = sqlx::query_as::<Postgres, (f64, bool)>(
+            "
+            INSERT INTO rate_limit_buckets (key, tokens, allowed, updated_at)
+            VALUES ($1, $2 - 1, true, $3)
+            ON CONFLICT (key) DO UPDATE SET
+                tokens = LEAST($2, rate_limit_buckets.tokens
+                    + ($3 - rate_limit_buckets.updated_at) * $4)
+                    - CASE WHEN LEAST($2, rate_limit_buckets.tokens
+                        + ($3 - rate_limit_buckets.updated_at) * $4) >= 1 THEN 1 ELSE 0 END,
+                allowed = LEAST($2, rate_limit_buckets.tokens
+                    + ($3 - rate_limit_buckets.updated_at) * $4) >= 1,
+                updated_at = $3
+            RETURNING tokens, allowed
+            ",
+        )
+        .bind(key)
+        .bind(policy.capacity as f64)
+        .bind(current_time)
+        .bind(policy.refill_per_milli())
+        .fetch_one(&self.pool)
+        .await;
+
+        match sqlx_result {
+            Ok((tokens, allowed)) => Ok(RateLimitDecision::new(allowed, tokens, policy)),
+            Err(e) => {
+                tracing::error!(%e);
+                Err(AppError::new("failed to take rate limit token"))
+            }
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{
-    auth::models::access_token_claims::AccessTokenClaims, memos::models::memo::Memo,
-    users::models::user::User, AppState,
+    auth::models::access_token_claims::AccessTokenClaims,
+    memos::{self, models::memo::Memo},
+    settings::{self, models::user_settings::UserSettings},
+    users::{self, models::user::User},
+    AppState,
 };
 
 use super::{
@@ -19,7 +22,24 @@ pub async fn sync(
     state: &AppState,
 ) -> Result<SyncData, ApiError> {
     let mut user: Option<User> = None;
+    let mut settings: Option<UserSettings> = None;
     let mut memos: Option<Vec<Memo>> = None;
 
-    Ok(SyncData { user, memos })
+    if dto.user == Some(true) {
+        user = Some(users::service::get_user_by_id(&claims.sub, state).await?);
+    }
+
+    if dto.settings == Some(true) {
+        settings = Some(settings::service::get_user_settings(&claims.sub, state).await?);
+    }
+
+    if let Some(memos_dto) = &dto.memos {
+        memos = Some(memos::service::get_memos(memos_dto, Some(claims), state).await?);
+    }
+
+    Ok(SyncData {
+        user,
+        settings,
+        memos,
+    })
 }
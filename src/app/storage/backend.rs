@@ -0,0 +1,12 @@
+use std::fmt::Debug;
+
+use axum::async_trait;
+
+use crate::app::models::app_error::AppError;
+
+#[async_trait]
+pub trait StorageBackend: Debug + Send + Sync {
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<(), AppError>;
+    async fn delete(&self, key: &str) -> Result<(), AppError>;
+    fn url(&self, key: &str) -> String;
+}
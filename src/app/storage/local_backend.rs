@@ -0,0 +1,67 @@
+use std::{io::ErrorKind, path::PathBuf};
+
+use axum::async_trait;
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::app::models::app_error::AppError;
+
+use super::backend::StorageBackend;
+
+// keys are generated by the app, never taken from user input
+#[derive(Debug)]
+pub struct LocalBackend {
+    root: PathBuf,
+    public_url: String,
+}
+
+impl LocalBackend {
+    pub fn new(root: &str, public_url: &str) -> Self {
+        Self {
+            root: PathBuf::from(root),
+            public_url: public_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl StorageBackend for LocalBackend {
+    async fn put(&self, key: &str, bytes: Vec<u8>, _content_type: &str) -> Result<(), AppError> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            if let Err(e) = fs::create_dir_all(parent).await {
+                tracing::error!(%e);
+                return Err(AppError::new("failed to create storage directory"));
+            }
+        }
+
+        // write then rename so readers never see a partial file
+        let tmp_path = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+        if let Err(e) = fs::write(&tmp_path, bytes).await {
+            tracing::error!(%e);
+            return Err(AppError::new("failed to write file"));
+        }
+        if let Err(e) = fs::rename(&tmp_path, &path).await {
+            tracing::error!(%e);
+            let _ = fs::remove_file(&tmp_path).await;
+            return Err(AppError::new("failed to move file"));
+        }
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        match fs::remove_file(self.root.join(key)).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => {
+                tracing::error!(%e);
+                Err(AppError::new("failed to delete file"))
+            }
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_url, key)
+    }
+}
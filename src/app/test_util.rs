@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use axum::{body, response::Response};
+use sqlx::{types::Uuid, Executor, PgPool};
+use tokio::sync::RwLock;
+
+use crate::{
+    app::{
+        envy::Envy,
+        fcm::{self, client::FcmClient},
+        models::app_state::AppState,
+        rate_limit::{self, limiter::RateLimiter, memory_backend::MemoryBackend},
+        storage::local_backend::LocalBackend,
+    },
+    auth::{
+        authman::AuthMan,
+        enums::token_type::TokenType,
+        jwks::keyring::Keyring,
+        models::access_token_claims::AccessTokenClaims,
+        password_policy::{
+            breached_passwords::BreachedPasswords, models::password_policy::PasswordPolicy,
+        },
+        util::password,
+    },
+    realtime::hub::RealtimeHub,
+};
+
+// state on the database created by #[sqlx::test], without identity providers or a mail server
+pub async fn app_state(pool: PgPool) -> AppState {
+    pool.execute(include_str!("../../migrations/applied/genesis.sql"))
+        .await
+        .expect("failed to apply genesis.sql");
+    pool.execute(include_str!("../../migrations/pending.sql"))
+        .await
+        .expect("failed to apply pending.sql");
+
+    let envy = envy();
+    let fcm_config = fcm::models::client_config::ClientConfig {
+        project_name: envy.fcm_project_name.to_owned(),
+        client_email: envy.fcm_client_email.to_owned(),
+        private_key: envy.fcm_private_key.to_owned(),
+    };
+    let keyring = Keyring::new(&envy.jwt_secret, Vec::new()).expect("failed to load keyring");
+    let password_policy = PasswordPolicy::new(8, 3, BreachedPasswords::new());
+    let argon2_params =
+        password::params(Some(8), Some(1), Some(1)).expect("failed to load argon2 params");
+    let authman = AuthMan::new(
+        Vec::new(),
+        Arc::new(RwLock::new(FcmClient::new(fcm_config))),
+        keyring,
+        password_policy,
+        argon2_params,
+    );
+    let rate_limiter = RateLimiter::new(
+        Arc::new(MemoryBackend::new()),
+        rate_limit::config::default_policies(),
+    );
+
+    AppState {
+        envy,
+        http_client: reqwest::Client::new(),
+        authman,
+        pool,
+        rate_limiter,
+        storage: Arc::new(LocalBackend::new("target/test-uploads", "/uploads")),
+        realtime: RealtimeHub::new(),
+    }
+}
+
+// a user with only the required columns, the username doubles as email name and displayname
+pub async fn insert_user(username: &str, state: &AppState) -> Uuid {
+    let id = Uuid::new_v4();
+    let email = format!("{}@example.com", username);
+    sqlx::query(
+        "
+        INSERT INTO users (id, username, username_key, email, email_key, displayname, updated_at, created_at)
+        VALUES ($1, $2, $2, $3, $3, $2, 0, 0)
+        ",
+    )
+    .bind(id)
+    .bind(username)
+    .bind(&email)
+    .execute(&state.pool)
+    .await
+    .expect("failed to insert user");
+
+    id
+}
+
+pub fn access_claims(user_id: &Uuid) -> AccessTokenClaims {
+    AccessTokenClaims::new(&user_id.to_string(), None, TokenType::ACCESS, &[])
+}
+
+pub async fn into_parts(response: Response) -> (u16, Vec<u8>) {
+    let status = response.status().as_u16();
+    let body = body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("failed to read body");
+
+    (status, body.to_vec())
+}
+
+fn envy() -> Envy {
+    Envy {
+        app_env: "test".to_string(),
+        api_url: "http://localhost".to_string(),
+        port: None,
+        trust_proxy: None,
+        rate_limit_backend: None,
+        rate_limits: None,
+        storage_backend: None,
+        storage_path: None,
+        storage_public_url: None,
+        database_url: String::new(),
+        jwt_secret: "test-jwt-secret".to_string(),
+        jwt_keys: None,
+        refresh_token_secret: "test-refresh-token-secret".to_string(),
+        api_key_secret: "test-api-key-secret".to_string(),
+        password_min_length: None,
+        password_min_score: None,
+        breached_passwords_path: None,
+        argon2_memory_cost: None,
+        argon2_time_cost: None,
+        argon2_parallelism: None,
+        apple_team_id: String::new(),
+        apple_client_id: String::new(),
+        apple_key_id: String::new(),
+        apple_private_key: String::new(),
+        google_client_id_ios: String::new(),
+        google_client_id_android: String::new(),
+        google_client_id_web: String::new(),
+        oidc_providers: None,
+        fcm_project_name: String::new(),
+        fcm_client_email: String::new(),
+        fcm_private_key: String::new(),
+        mail_port: 1,
+        mail_host: "localhost".to_string(),
+        mail_from: "Perroquet <noreply@localhost>".to_string(),
+        mail_user: String::new(),
+        mail_pass: String::new(),
+    }
+}
@@ -3,9 +3,12 @@ use std::{collections::HashMap, time::Instant};
 use axum::http::StatusCode;
 use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
 
-use crate::app::{
-    self,
-    models::{api_error::ApiError, app_error::AppError},
+use crate::{
+    app::{
+        self,
+        models::{api_error::ApiError, app_error::AppError},
+    },
+    auth::enums::client_type::ClientType,
 };
 
 use super::{
@@ -42,12 +45,10 @@ impl AppleAuthClient {
     }
 
     pub async fn login(&mut self, http_client: &reqwest::Client) -> Result<(), AppError> {
-        let client_secret_ios =
-            Self::generate_client_secret(self.config.clone(), "ios".to_string())?;
+        let client_secret_ios = Self::generate_client_secret(&self.config, ClientType::IOS)?;
         let client_secret_android =
-            Self::generate_client_secret(self.config.clone(), "android".to_string())?;
-        let client_secret_web =
-            Self::generate_client_secret(self.config.clone(), "web".to_string())?;
+            Self::generate_client_secret(&self.config, ClientType::ANDROID)?;
+        let client_secret_web = Self::generate_client_secret(&self.config, ClientType::WEB)?;
 
         let result = http_client
             .get("https://appleid.apple.com/auth/keys")
@@ -75,15 +76,10 @@ impl AppleAuthClient {
     }
 
     fn generate_client_secret(
-        config: ClientConfig,
-        client_type: String,
+        config: &ClientConfig,
+        client_type: &str,
     ) -> Result<String, AppError> {
-        let client_id = match client_type.as_ref() {
-            "ios" => &config.client_id_ios,
-            "android" => &config.client_id_android,
-            "web" => &config.client_id_web,
-            _ => &config.client_id_ios,
-        };
+        let client_id = config.client_id(client_type);
 
         let current_time_in_secs = app::util::time::current_time_in_secs();
         let claims = serde_json::json!(ClientClaims {
@@ -112,19 +108,13 @@ impl AppleAuthClient {
     pub async fn validate_auth_code(
         &self,
         auth_code: &str,
-        client_type: String,
+        client_type: &str,
         http_client: &reqwest::Client,
     ) -> Result<AppleAuthCodeResponse, ApiError> {
-        let client_id = match client_type.as_ref() {
-            "ios" => &self.config.client_id_ios,
-            "android" => &self.config.client_id_android,
-            "web" => &self.config.client_id_web,
-            _ => &self.config.client_id_ios,
-        };
-        let client_secret = match client_type.as_ref() {
-            "ios" => &self.client_secret_ios,
-            "android" => &self.client_secret_android,
-            "web" => &self.client_secret_web,
+        let client_id = self.config.client_id(client_type);
+        let client_secret = match client_type {
+            ClientType::ANDROID => &self.client_secret_android,
+            ClientType::WEB => &self.client_secret_web,
             _ => &self.client_secret_ios,
         };
 
@@ -133,11 +123,8 @@ impl AppleAuthClient {
         form.insert("client_secret", client_secret.to_string());
         form.insert("code", auth_code.to_string());
         form.insert("grant_type", "authorization_code".to_string());
-        if client_type == "web" {
-            form.insert(
-                "redirect_uri",
-                "https://perroquet.beamcove.com/signin/apple".to_string(),
-            );
+        if client_type == ClientType::WEB {
+            form.insert("redirect_uri", self.config.redirect_uri.to_string());
         }
 
         let result = http_client
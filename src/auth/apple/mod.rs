@@ -1,3 +1,4 @@
 pub mod client;
 pub mod models;
+pub mod provider;
 pub mod responses;
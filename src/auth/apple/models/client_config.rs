@@ -1,3 +1,5 @@
+use crate::auth::enums::client_type::ClientType;
+
 #[derive(Debug, Clone)]
 pub struct ClientConfig {
     pub team_id: String,
@@ -6,4 +8,15 @@ pub struct ClientConfig {
     pub client_id_web: String,
     pub key_id: String,
     pub private_key: String,
+    pub redirect_uri: String,
+}
+
+impl ClientConfig {
+    pub fn client_id(&self, client_type: &str) -> &str {
+        match client_type {
+            ClientType::ANDROID => &self.client_id_android,
+            ClientType::WEB => &self.client_id_web,
+            _ => &self.client_id_ios,
+        }
+    }
 }
@@ -0,0 +1,75 @@
+use axum::{async_trait, http::StatusCode};
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+use crate::{
+    app::models::api_error::ApiError,
+    auth::{
+        dtos::provider_credential_dto::ProviderCredentialDto,
+        enums::client_type::ClientType,
+        providers::{
+            identity_provider::IdentityProvider, models::verified_identity::VerifiedIdentity,
+        },
+    },
+};
+
+use super::client::AppleAuthClient;
+
+#[derive(Debug)]
+pub struct AppleProvider {
+    client: RwLock<AppleAuthClient>,
+}
+
+impl AppleProvider {
+    pub fn new(client: AppleAuthClient) -> Self {
+        Self {
+            client: RwLock::new(client),
+        }
+    }
+
+    async fn client(&self, http_client: &reqwest::Client) -> RwLockReadGuard<'_, AppleAuthClient> {
+        let readable_apple_client = self.client.read().await;
+        if readable_apple_client.expired() {
+            drop(readable_apple_client);
+            let mut writable_apple_client = self.client.write().await;
+            let _ = writable_apple_client.login(http_client).await;
+        }
+
+        self.client.read().await
+    }
+}
+
+#[async_trait]
+impl IdentityProvider for AppleProvider {
+    fn name(&self) -> &str {
+        "apple"
+    }
+
+    async fn verify(
+        &self,
+        dto: &ProviderCredentialDto,
+        http_client: &reqwest::Client,
+    ) -> Result<VerifiedIdentity, ApiError> {
+        let Some(auth_code) = &dto.auth_code else {
+            return Err(ApiError::new(StatusCode::BAD_REQUEST, "Missing auth_code."));
+        };
+        let client_type = dto.client.as_deref().unwrap_or(ClientType::IOS);
+
+        let apple_client = self.client(http_client).await;
+        let auth_code_res = apple_client
+            .validate_auth_code(auth_code, client_type, http_client)
+            .await?;
+
+        let Ok(claims) = auth_code_res.decode_id_token(
+            &apple_client.public_keys,
+            apple_client.config.client_id(client_type),
+        ) else {
+            return Err(ApiError::internal_server_error());
+        };
+
+        Ok(VerifiedIdentity {
+            subject: claims.sub,
+            email: Some(claims.email),
+            email_verified: claims.email_verified,
+        })
+    }
+}
@@ -1,40 +1,55 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
+use argon2::Params;
+use axum::http::StatusCode;
 use tokio::sync::RwLock;
 
-use crate::app::fcm::client::FcmClient;
+use crate::app::{fcm::client::FcmClient, models::api_error::ApiError};
 
-use super::apple::client::AppleAuthClient;
+use super::{
+    jwks::keyring::Keyring, password_policy::models::password_policy::PasswordPolicy,
+    providers::identity_provider::IdentityProvider,
+};
 
 #[derive(Debug, Clone)]
 pub struct AuthMan {
-    apple_client: Arc<RwLock<AppleAuthClient>>,
+    identity_providers: Arc<HashMap<String, Arc<dyn IdentityProvider>>>,
     fcm_client: Arc<RwLock<FcmClient>>,
+    keyring: Keyring,
+    password_policy: Arc<PasswordPolicy>,
+    argon2_params: Params,
 }
 
 impl AuthMan {
     pub fn new(
-        apple_client: Arc<RwLock<AppleAuthClient>>,
+        identity_providers: Vec<Arc<dyn IdentityProvider>>,
         fcm_client: Arc<RwLock<FcmClient>>,
+        keyring: Keyring,
+        password_policy: PasswordPolicy,
+        argon2_params: Params,
     ) -> Self {
+        let mut providers = HashMap::new();
+        for identity_provider in identity_providers {
+            let name = identity_provider.name().to_string();
+            if providers.insert(name, identity_provider).is_some() {
+                panic!("duplicate identity provider");
+            }
+        }
+
         Self {
-            apple_client,
+            identity_providers: Arc::new(providers),
             fcm_client,
+            keyring,
+            password_policy: Arc::new(password_policy),
+            argon2_params,
         }
     }
 
-    pub async fn apple_client(
-        &self,
-        http_client: &reqwest::Client,
-    ) -> Arc<RwLock<AppleAuthClient>> {
-        let readable_apple_client = self.apple_client.read().await;
-        if readable_apple_client.expired() {
-            drop(readable_apple_client);
-            let mut writable_apple_client = self.apple_client.write().await;
-            let _ = writable_apple_client.login(http_client).await;
+    pub fn identity_provider(&self, name: &str) -> Result<Arc<dyn IdentityProvider>, ApiError> {
+        match self.identity_providers.get(name) {
+            Some(identity_provider) => Ok(identity_provider.clone()),
+            None => Err(ApiError::new(StatusCode::NOT_FOUND, "Provider not found.")),
         }
-
-        self.apple_client.clone()
     }
 
     pub async fn fcm_client(&self, http_client: &reqwest::Client) -> Arc<RwLock<FcmClient>> {
@@ -47,4 +62,16 @@ impl AuthMan {
 
         self.fcm_client.clone()
     }
+
+    pub fn keyring(&self) -> &Keyring {
+        &self.keyring
+    }
+
+    pub fn password_policy(&self) -> &PasswordPolicy {
+        &self.password_policy
+    }
+
+    pub fn argon2_params(&self) -> &Params {
+        &self.argon2_params
+    }
 }
@@ -1 +1,13 @@
 pub static JWT_EXP: u64 = 3600;
+pub static SIGNIN_CHALLENGE_EXP: u64 = 300;
+pub static UNSUBSCRIBE_DIGEST_EXP: u64 = 7776000;
+pub static REFRESH_TOKEN_IDLE_EXP: u64 = 2592000;
+pub static REFRESH_TOKEN_ABSOLUTE_EXP: u64 = 7776000;
+pub static WEBAUTHN_CHALLENGE_EXP: u64 = 300;
+pub static MAGIC_LINK_EXP: u64 = 900;
+pub static MAGIC_LINK_MAX_REQUESTS: i64 = 3;
+pub static SIGNIN_ACCOUNT_FREE_ATTEMPTS: i32 = 5;
+pub static SIGNIN_IP_FREE_ATTEMPTS: i32 = 20;
+pub static SIGNIN_MAX_LOCKOUT: u64 = 3600;
+pub static SIGNIN_ATTEMPTS_RESET: u64 = 86400;
+pub static SIGNIN_ALERT_THRESHOLD: i32 = 10;
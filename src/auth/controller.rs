@@ -1,8 +1,8 @@
 use axum::{
     body::Body,
-    extract::State,
+    extract::{Path, State},
     http::{HeaderValue, StatusCode},
-    response::Response,
+    response::{IntoResponse, Response},
     Json,
 };
 use axum_extra::extract::CookieJar;
@@ -10,29 +10,43 @@ use cookie::{
     time::{Duration, OffsetDateTime},
     Cookie, SameSite,
 };
+use jsonwebtoken::jwk::JwkSet;
 use validator::Validate;
 
-use crate::{app::models::api_error::ApiError, AppState};
+use crate::{
+    app::models::{api_error::ApiError, client_ip::ClientIp},
+    passkeys::models::passkey_options::PasskeyOptions,
+    AppState,
+};
 
 use super::{
+    config::REFRESH_TOKEN_ABSOLUTE_EXP,
     dtos::{
-        edit_password_dto::EditPasswordDto, refresh_access_info_dto::RefreshAccessInfoDto,
+        edit_password_dto::EditPasswordDto, provider_credential_dto::ProviderCredentialDto,
+        refresh_access_info_dto::RefreshAccessInfoDto,
         request_email_update_dto::RequestEmailUpdateDto,
-        request_password_update_dto::RequestPasswordUpdateDto, signin_apple_dto::SigninAppleDto,
-        signin_dto::SigninDto, signout_dto::SignoutDto, signup_dto::SignupDto,
+        request_magic_link_dto::RequestMagicLinkDto,
+        request_password_update_dto::RequestPasswordUpdateDto, signin_dto::SigninDto,
+        signin_magic_link_dto::SigninMagicLinkDto, signin_passkey_dto::SigninPasskeyDto,
+        signout_dto::SignoutDto, signup_dto::SignupDto, totp_code_dto::TotpCodeDto,
     },
+    enums::scope::Scope,
     models::{
         access_info::AccessInfo,
         access_token_claims::{
             ExtractClaims, ExtractClaimsPepperEditEmail, ExtractClaimsPepperEditPassword,
+            ExtractClaimsPepperSigninChallenge,
         },
+        recovery_codes::RecoveryCodes,
+        signin_response::SigninResponse,
+        totp_enrollment::TotpEnrollment,
     },
     service,
 };
 
 fn cookified_access_info_response(access_info: AccessInfo) -> Response {
     let mut now = OffsetDateTime::now_utc();
-    now += Duration::weeks(52);
+    now += Duration::seconds(REFRESH_TOKEN_ABSOLUTE_EXP as i64);
 
     let mut refresh_token_cookie = Cookie::new("refresh_token", access_info.refresh_token.clone());
     refresh_token_cookie.set_same_site(SameSite::Lax);
@@ -57,39 +71,99 @@ fn cookified_access_info_response(access_info: AccessInfo) -> Response {
         .unwrap()
 }
 
+pub async fn get_jwks(State(state): State<AppState>) -> Result<Json<JwkSet>, ApiError> {
+    match service::get_jwks(&state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
 pub async fn signup(
     State(state): State<AppState>,
     Json(dto): Json<SignupDto>,
+) -> Result<(), ApiError> {
+    dto.validate()?;
+    service::signup(&dto, &state).await
+}
+
+pub async fn signin(
+    State(state): State<AppState>,
+    ClientIp(client_ip): ClientIp,
+    Json(dto): Json<SigninDto>,
 ) -> Result<Response, ApiError> {
     dto.validate()?;
-    match service::signup(&dto, &state).await {
-        Ok(data) => Ok(cookified_access_info_response(data)),
+    match service::signin(&dto, &client_ip, &state).await {
+        Ok(SigninResponse::Access(data)) => Ok(cookified_access_info_response(data)),
+        Ok(SigninResponse::Challenge(data)) => Ok(Json(data).into_response()),
         Err(e) => Err(e),
     }
 }
 
-pub async fn signin(
+pub async fn request_magic_link(
     State(state): State<AppState>,
-    Json(dto): Json<SigninDto>,
+    Json(dto): Json<RequestMagicLinkDto>,
+) -> Result<(), ApiError> {
+    dto.validate()?;
+    service::request_magic_link(&dto, &state).await
+}
+
+pub async fn signin_magic_link(
+    State(state): State<AppState>,
+    Json(dto): Json<SigninMagicLinkDto>,
 ) -> Result<Response, ApiError> {
     dto.validate()?;
-    match service::signin(&dto, &state).await {
+    match service::signin_magic_link(&dto, &state).await {
+        Ok(SigninResponse::Access(data)) => Ok(cookified_access_info_response(data)),
+        Ok(SigninResponse::Challenge(data)) => Ok(Json(data).into_response()),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn signin_totp(
+    State(state): State<AppState>,
+    ExtractClaimsPepperSigninChallenge(claims): ExtractClaimsPepperSigninChallenge,
+    Json(dto): Json<TotpCodeDto>,
+) -> Result<Response, ApiError> {
+    dto.validate()?;
+    match service::signin_totp(&dto, &claims, &state).await {
         Ok(data) => Ok(cookified_access_info_response(data)),
         Err(e) => Err(e),
     }
 }
 
-pub async fn signin_apple(
+pub async fn request_passkey_signin(
     State(state): State<AppState>,
-    Json(dto): Json<SigninAppleDto>,
+) -> Result<Json<PasskeyOptions>, ApiError> {
+    match service::request_passkey_signin(&state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn signin_passkey(
+    State(state): State<AppState>,
+    Json(dto): Json<SigninPasskeyDto>,
 ) -> Result<Response, ApiError> {
     dto.validate()?;
-    match service::signin_apple(&dto, &state).await {
+    match service::signin_passkey(&dto, &state).await {
         Ok(data) => Ok(cookified_access_info_response(data)),
         Err(e) => Err(e),
     }
 }
 
+pub async fn signin_provider(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    Json(dto): Json<ProviderCredentialDto>,
+) -> Result<Response, ApiError> {
+    dto.validate()?;
+    match service::signin_provider(&provider, &dto, &state).await {
+        Ok(SigninResponse::Access(data)) => Ok(cookified_access_info_response(data)),
+        Ok(SigninResponse::Challenge(data)) => Ok(Json(data).into_response()),
+        Err(e) => Err(e),
+    }
+}
+
 pub async fn refresh(
     State(state): State<AppState>,
     jar: CookieJar,
@@ -125,6 +199,7 @@ pub async fn signout(
     ExtractClaims(claims): ExtractClaims,
     Json(dto): Json<SignoutDto>,
 ) -> Result<(), ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
     dto.validate()?;
     service::signout(&dto, &claims, &state).await
 }
@@ -134,6 +209,7 @@ pub async fn request_email_update(
     ExtractClaims(claims): ExtractClaims,
     Json(dto): Json<RequestEmailUpdateDto>,
 ) -> Result<(), ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
     dto.validate()?;
     service::request_email_update(&dto, &claims, &state).await
 }
@@ -161,3 +237,123 @@ pub async fn edit_password(
     dto.validate()?;
     service::edit_password(&dto, &claims, &state).await
 }
+
+pub async fn request_totp(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<Json<TotpEnrollment>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    match service::request_totp(&claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn enable_totp(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+    Json(dto): Json<TotpCodeDto>,
+) -> Result<Json<RecoveryCodes>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    dto.validate()?;
+    match service::enable_totp(&dto, &claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn disable_totp(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+    Json(dto): Json<TotpCodeDto>,
+) -> Result<(), ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    dto.validate()?;
+    service::disable_totp(&dto, &claims, &state).await
+}
+
+pub async fn regenerate_recovery_codes(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+    Json(dto): Json<TotpCodeDto>,
+) -> Result<Json<RecoveryCodes>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    dto.validate()?;
+    match service::regenerate_recovery_codes(&dto, &claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{extract::State, response::IntoResponse, Json};
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    use crate::app::test_util;
+
+    use super::{request_password_update, signup};
+
+    static PASSWORD: &str = "violet-harbor-lantern-1987";
+
+    async fn signup_response(
+        state: &crate::AppState,
+        username: Option<&str>,
+        email: &str,
+    ) -> (u16, Vec<u8>) {
+        let dto = serde_json::from_value(json!({
+            "username": username,
+            "email": email,
+            "password": PASSWORD,
+        }))
+        .unwrap();
+        let response = signup(State(state.clone()), Json(dto))
+            .await
+            .into_response();
+
+        test_util::into_parts(response).await
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn signup_responds_the_same_for_existing_email(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        signup_response(&state, Some("existing"), "existing@example.com").await;
+
+        let existing = signup_response(&state, Some("someone"), "existing@example.com").await;
+        let fresh = signup_response(&state, Some("someoneelse"), "fresh@example.com").await;
+
+        assert_eq!(existing.0, 200);
+        assert_eq!(existing, fresh);
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn signup_responds_the_same_for_taken_username(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        signup_response(&state, Some("taken"), "existing@example.com").await;
+
+        let existing = signup_response(&state, Some("taken"), "existing@example.com").await;
+        let fresh = signup_response(&state, Some("taken"), "fresh@example.com").await;
+
+        assert_eq!(existing.0, 409);
+        assert_eq!(existing, fresh);
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn request_password_update_responds_the_same_for_existing_email(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        signup_response(&state, Some("existing"), "existing@example.com").await;
+
+        let mut responses = Vec::new();
+        for email in ["existing@example.com", "fresh@example.com"] {
+            let dto = serde_json::from_value(json!({ "email": email })).unwrap();
+            let response = request_password_update(State(state.clone()), Json(dto))
+                .await
+                .into_response();
+            responses.push(test_util::into_parts(response).await);
+        }
+
+        assert_eq!(responses[0].0, 200);
+        assert_eq!(responses[0], responses[1]);
+    }
+}
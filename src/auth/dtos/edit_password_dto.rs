@@ -3,9 +3,10 @@ use validator::Validate;
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct EditPasswordDto {
-    #[validate(
-        length(min = 8, max = 64, message = "password must be least 8 characters."),
-        custom = "super::validate_password"
-    )]
+    #[validate(length(
+        min = 1,
+        max = 64,
+        message = "password must be between 1 and 64 characters."
+    ))]
     pub password: String,
 }
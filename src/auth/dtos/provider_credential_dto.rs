@@ -2,7 +2,8 @@ use serde::Deserialize;
 use validator::Validate;
 
 #[derive(Debug, Deserialize, Validate)]
-pub struct SigninAppleDto {
-    pub auth_code: String,
+pub struct ProviderCredentialDto {
+    pub auth_code: Option<String>,
+    pub id_token: Option<String>,
     pub client: Option<String>,
 }
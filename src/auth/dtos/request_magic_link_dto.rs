@@ -0,0 +1,8 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RequestMagicLinkDto {
+    #[validate(email)]
+    pub email: String,
+}
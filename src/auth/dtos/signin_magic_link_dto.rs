@@ -0,0 +1,7 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SigninMagicLinkDto {
+    pub token: String,
+}
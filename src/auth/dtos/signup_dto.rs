@@ -12,9 +12,10 @@ pub struct SignupDto {
     pub username: Option<String>,
     #[validate(email)]
     pub email: String,
-    #[validate(
-        length(min = 8, max = 64, message = "password must be least 8 characters."),
-        custom = "super::validate_password"
-    )]
+    #[validate(length(
+        min = 1,
+        max = 64,
+        message = "password must be between 1 and 64 characters."
+    ))]
     pub password: String,
 }
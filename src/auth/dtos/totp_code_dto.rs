@@ -0,0 +1,12 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct TotpCodeDto {
+    #[validate(length(
+        min = 6,
+        max = 11,
+        message = "code must be between 6 and 11 characters."
+    ))]
+    pub code: String,
+}
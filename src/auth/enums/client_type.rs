@@ -0,0 +1,8 @@
+#[non_exhaustive]
+pub struct ClientType;
+
+impl ClientType {
+    pub const IOS: &'static str = "ios";
+    pub const ANDROID: &'static str = "android";
+    pub const WEB: &'static str = "web";
+}
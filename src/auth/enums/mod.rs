@@ -1 +1,6 @@
+pub mod client_type;
 pub mod pepper_type;
+pub mod scope;
+pub mod secret_token_domain;
+pub mod token_type;
+pub mod webauthn_ceremony;
@@ -5,4 +5,6 @@ impl PepperType {
     pub const VERIFY_EMAIL: &'static str = "verify-email";
     pub const EDIT_EMAIL: &'static str = "edit-email";
     pub const EDIT_PASSWORD: &'static str = "edit-password";
+    pub const SIGNIN_CHALLENGE: &'static str = "signin-challenge";
+    pub const UNSUBSCRIBE_DIGEST: &'static str = "unsubscribe-digest";
 }
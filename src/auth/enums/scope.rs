@@ -0,0 +1,16 @@
+#[non_exhaustive]
+pub struct Scope;
+
+impl Scope {
+    pub const ACCOUNT: &'static str = "account";
+    pub const USERS_READ: &'static str = "users:read";
+    pub const MEMOS_READ: &'static str = "memos:read";
+    pub const MEMOS_WRITE: &'static str = "memos:write";
+
+    pub const ALL: [&'static str; 4] = [
+        Self::ACCOUNT,
+        Self::USERS_READ,
+        Self::MEMOS_READ,
+        Self::MEMOS_WRITE,
+    ];
+}
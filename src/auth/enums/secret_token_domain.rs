@@ -0,0 +1,9 @@
+#[non_exhaustive]
+pub struct SecretTokenDomain;
+
+impl SecretTokenDomain {
+    pub const REFRESH_TOKEN: &'static str = "refresh-token";
+    pub const API_KEY: &'static str = "api-key";
+    pub const MAGIC_LINK: &'static str = "magic-link";
+    pub const RECOVERY_CODE: &'static str = "recovery-code";
+}
@@ -0,0 +1,12 @@
+#[non_exhaustive]
+pub struct TokenType;
+
+impl TokenType {
+    pub const ACCESS: &'static str = "access";
+    pub const API_KEY: &'static str = "api-key";
+    pub const VERIFY_EMAIL: &'static str = "verify-email";
+    pub const EDIT_EMAIL: &'static str = "edit-email";
+    pub const EDIT_PASSWORD: &'static str = "edit-password";
+    pub const SIGNIN_CHALLENGE: &'static str = "signin-challenge";
+    pub const UNSUBSCRIBE_DIGEST: &'static str = "unsubscribe-digest";
+}
@@ -0,0 +1,7 @@
+#[non_exhaustive]
+pub struct WebauthnCeremony;
+
+impl WebauthnCeremony {
+    pub const REGISTRATION: &'static str = "registration";
+    pub const AUTHENTICATION: &'static str = "authentication";
+}
@@ -0,0 +1,89 @@
+use std::time::Instant;
+
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+
+use crate::app::models::app_error::AppError;
+
+use super::{
+    models::{client_config::ClientConfig, id_token_claims::IdTokenClaims, public_key::PublicKey},
+    responses::google_public_keys_res::GooglePublicKeysResponse,
+};
+
+#[derive(Debug, Clone)]
+pub struct GoogleAuthClient {
+    pub config: ClientConfig,
+    pub public_keys: Vec<PublicKey>,
+    refreshed_at: Instant,
+}
+
+impl GoogleAuthClient {
+    pub fn new(config: ClientConfig) -> Self {
+        Self {
+            config,
+            public_keys: Vec::new(),
+            refreshed_at: Instant::now(),
+        }
+    }
+
+    pub fn expired(&self) -> bool {
+        self.refreshed_at.elapsed().as_secs() > 3600
+    }
+
+    pub async fn login(&mut self, http_client: &reqwest::Client) -> Result<(), AppError> {
+        let result = http_client
+            .get("https://www.googleapis.com/oauth2/v3/certs")
+            .send()
+            .await;
+
+        let Ok(response) = result else {
+            return Err(AppError::new("failed to get google public keys"));
+        };
+        let Ok(text) = response.text().await else {
+            return Err(AppError::new("failed to get response text"));
+        };
+        let Ok(google_public_keys_res) = serde_json::from_str::<GooglePublicKeysResponse>(&text)
+        else {
+            return Err(AppError::new("failed to decode public keys from text"));
+        };
+
+        self.public_keys = google_public_keys_res.keys;
+        self.refreshed_at = Instant::now();
+
+        Ok(())
+    }
+
+    pub fn decode_id_token(&self, id_token: &str) -> Result<IdTokenClaims, AppError> {
+        let Ok(id_token_header) = jsonwebtoken::decode_header(id_token) else {
+            return Err(AppError::new("failed to decode id_token header"));
+        };
+
+        let Some(kid) = id_token_header.kid else {
+            return Err(AppError::new("id_token_header has no kid"));
+        };
+
+        let Some(public_key) = self.public_keys.iter().find(|key| key.kid == kid) else {
+            return Err(AppError::new("no public key matched id_token"));
+        };
+
+        let Ok(decoding_key) = DecodingKey::from_rsa_components(&public_key.n, &public_key.e)
+        else {
+            return Err(AppError::new("failed to create decoding key"));
+        };
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&[
+            &self.config.client_id_ios,
+            &self.config.client_id_android,
+            &self.config.client_id_web,
+        ]);
+        validation.set_issuer(&["accounts.google.com", "https://accounts.google.com"]);
+
+        match jsonwebtoken::decode::<IdTokenClaims>(id_token, &decoding_key, &validation) {
+            Ok(data) => Ok(data.claims),
+            Err(e) => {
+                tracing::error!(%e);
+                Err(AppError::new("failed to decode id_token"))
+            }
+        }
+    }
+}
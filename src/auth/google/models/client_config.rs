@@ -0,0 +1,6 @@
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    pub client_id_ios: String,
+    pub client_id_android: String,
+    pub client_id_web: String,
+}
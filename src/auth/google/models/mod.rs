@@ -0,0 +1,3 @@
+pub mod client_config;
+pub mod id_token_claims;
+pub mod public_key;
@@ -0,0 +1,69 @@
+use axum::{async_trait, http::StatusCode};
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+use crate::{
+    app::models::api_error::ApiError,
+    auth::{
+        dtos::provider_credential_dto::ProviderCredentialDto,
+        providers::{
+            identity_provider::IdentityProvider, models::verified_identity::VerifiedIdentity,
+        },
+    },
+};
+
+use super::client::GoogleAuthClient;
+
+#[derive(Debug)]
+pub struct GoogleProvider {
+    client: RwLock<GoogleAuthClient>,
+}
+
+impl GoogleProvider {
+    pub fn new(client: GoogleAuthClient) -> Self {
+        Self {
+            client: RwLock::new(client),
+        }
+    }
+
+    async fn client(&self, http_client: &reqwest::Client) -> RwLockReadGuard<'_, GoogleAuthClient> {
+        let readable_google_client = self.client.read().await;
+        if readable_google_client.expired() {
+            drop(readable_google_client);
+            let mut writable_google_client = self.client.write().await;
+            let _ = writable_google_client.login(http_client).await;
+        }
+
+        self.client.read().await
+    }
+}
+
+#[async_trait]
+impl IdentityProvider for GoogleProvider {
+    fn name(&self) -> &str {
+        "google"
+    }
+
+    async fn verify(
+        &self,
+        dto: &ProviderCredentialDto,
+        http_client: &reqwest::Client,
+    ) -> Result<VerifiedIdentity, ApiError> {
+        let Some(id_token) = &dto.id_token else {
+            return Err(ApiError::new(StatusCode::BAD_REQUEST, "Missing id_token."));
+        };
+
+        let google_client = self.client(http_client).await;
+        let Ok(claims) = google_client.decode_id_token(id_token) else {
+            return Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "Failed to verify Google id token.",
+            ));
+        };
+
+        Ok(VerifiedIdentity {
+            subject: claims.sub,
+            email: Some(claims.email),
+            email_verified: claims.email_verified,
+        })
+    }
+}
@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+use crate::auth::google::models::public_key::PublicKey;
+
+#[derive(Debug, Deserialize)]
+pub struct GooglePublicKeysResponse {
+    #[serde(rename(deserialize = "keys"))]
+    pub keys: Vec<PublicKey>,
+}
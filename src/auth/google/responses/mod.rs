@@ -0,0 +1 @@
+pub mod google_public_keys_res;
@@ -0,0 +1,246 @@
+use std::{fmt, sync::Arc};
+
+use jsonwebtoken::{
+    errors::{Error, ErrorKind},
+    jwk::JwkSet,
+    Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    app::{models::app_error::AppError, util::time},
+    auth::config::JWT_EXP,
+};
+
+use super::models::{key_config::KeyConfig, signing_key::SigningKey};
+
+#[derive(Clone)]
+pub struct Keyring {
+    secret: String,
+    keys: Arc<Vec<SigningKey>>,
+}
+
+impl fmt::Debug for Keyring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kids: Vec<&str> = self.keys.iter().map(|key| key.kid.as_str()).collect();
+        f.debug_struct("Keyring").field("kids", &kids).finish()
+    }
+}
+
+impl Keyring {
+    pub fn new(secret: &str, key_configs: Vec<KeyConfig>) -> Result<Self, AppError> {
+        let mut keys = Vec::new();
+        for key_config in key_configs {
+            keys.push(SigningKey::new(key_config)?);
+        }
+
+        let keyring = Self {
+            secret: secret.to_string(),
+            keys: Arc::new(keys),
+        };
+        if !keyring.keys.is_empty() && keyring.signing_key().is_none() {
+            return Err(AppError::new("no jwt key is active"));
+        }
+
+        Ok(keyring)
+    }
+
+    fn signing_key(&self) -> Option<&SigningKey> {
+        let current_time_in_secs = time::current_time_in_secs();
+
+        self.keys
+            .iter()
+            .filter(|key| key.signable(current_time_in_secs))
+            .max_by_key(|key| key.activates_at)
+    }
+
+    fn verifying_key(&self, kid: &str) -> Option<&SigningKey> {
+        let current_time_in_secs = time::current_time_in_secs();
+
+        self.keys
+            .iter()
+            .find(|key| key.kid == kid && key.verifiable(current_time_in_secs))
+    }
+
+    // access tokens signed with the shared secret are only accepted until the last one issued
+    // before the first asymmetric key activated has expired
+    fn accepts_secret_access_tokens(&self) -> bool {
+        let current_time_in_secs = time::current_time_in_secs();
+        let cut_over = self
+            .keys
+            .iter()
+            .map(|key| key.activates_at)
+            .filter(|activates_at| *activates_at <= current_time_in_secs)
+            .min();
+
+        match cut_over {
+            Some(cut_over) => current_time_in_secs < cut_over + JWT_EXP as i64,
+            None => true,
+        }
+    }
+
+    fn peppered_secret(&self, pepper: Option<&str>) -> String {
+        match pepper {
+            Some(pepper) => [&self.secret, pepper].concat(),
+            None => self.secret.to_string(),
+        }
+    }
+
+    // peppered tokens never leave the api, so they stay on the shared secret. once keys are
+    // configured, access tokens are never signed with the shared secret again
+    pub fn encode<T: Serialize>(
+        &self,
+        claims: &T,
+        pepper: Option<&str>,
+    ) -> Result<String, AppError> {
+        let result = match (pepper, self.keys.is_empty()) {
+            (None, false) => {
+                let Some(key) = self.signing_key() else {
+                    return Err(AppError::new("no jwt key is active"));
+                };
+                let mut header = Header::new(key.algorithm);
+                header.kid = Some(key.kid.to_string());
+
+                jsonwebtoken::encode(&header, claims, &key.encoding_key)
+            }
+            _ => {
+                let secret = self.peppered_secret(pepper);
+                jsonwebtoken::encode(
+                    &Header::default(),
+                    claims,
+                    &EncodingKey::from_secret(secret.as_bytes()),
+                )
+            }
+        };
+
+        match result {
+            Ok(jwt) => Ok(jwt),
+            Err(e) => {
+                tracing::error!(%e);
+                Err(AppError::new("failed to encode claims"))
+            }
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(
+        &self,
+        jwt: &str,
+        pepper: Option<&str>,
+        validate_exp: bool,
+    ) -> Result<T, Error> {
+        let header = jsonwebtoken::decode_header(jwt)?;
+
+        let mut validation;
+        let decoding_key;
+        match (header.kid, pepper) {
+            (Some(kid), None) => {
+                let Some(key) = self.verifying_key(&kid) else {
+                    return Err(ErrorKind::InvalidSignature.into());
+                };
+                validation = Validation::new(key.algorithm);
+                decoding_key = key.decoding_key.clone();
+            }
+            (None, None) if !self.accepts_secret_access_tokens() => {
+                return Err(ErrorKind::InvalidSignature.into());
+            }
+            _ => {
+                let secret = self.peppered_secret(pepper);
+                validation = Validation::new(Algorithm::HS256);
+                decoding_key = DecodingKey::from_secret(secret.as_bytes());
+            }
+        }
+        validation.validate_exp = validate_exp;
+
+        match jsonwebtoken::decode::<T>(jwt, &decoding_key, &validation) {
+            Ok(data) => Ok(data.claims),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn jwks(&self) -> JwkSet {
+        let current_time_in_secs = time::current_time_in_secs();
+
+        JwkSet {
+            keys: self
+                .keys
+                .iter()
+                .filter(|key| key.verifiable(current_time_in_secs))
+                .map(|key| key.jwk.clone())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::pkey::PKey;
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn key_config(kid: &str, activates_at: i64, retires_at: Option<i64>) -> KeyConfig {
+        let private_key = PKey::generate_ed25519()
+            .unwrap()
+            .private_key_to_pem_pkcs8()
+            .unwrap();
+
+        KeyConfig {
+            kid: kid.to_string(),
+            alg: Algorithm::EdDSA,
+            private_key: String::from_utf8(private_key).unwrap(),
+            activates_at,
+            retires_at,
+        }
+    }
+
+    #[test]
+    fn signs_with_the_newest_active_key() {
+        let current_time = time::current_time_in_secs();
+        let keyring = Keyring::new(
+            "secret",
+            vec![
+                key_config("old", current_time - 100, None),
+                key_config("new", current_time - 10, None),
+                key_config("next", current_time + 100, None),
+            ],
+        )
+        .unwrap();
+        let claims = json!({ "sub": "someone", "exp": current_time + 60 });
+
+        let jwt = keyring.encode(&claims, None).unwrap();
+        let header = jsonwebtoken::decode_header(&jwt).unwrap();
+        assert_eq!(header.kid.as_deref(), Some("new"));
+        assert_eq!(keyring.decode::<Value>(&jwt, None, true).unwrap(), claims);
+
+        let jwt = keyring.encode(&claims, Some("pepper")).unwrap();
+        assert_eq!(jsonwebtoken::decode_header(&jwt).unwrap().kid, None);
+    }
+
+    #[test]
+    fn uses_the_shared_secret_without_keys() {
+        let keyring = Keyring::new("secret", Vec::new()).unwrap();
+        let claims = json!({ "sub": "someone", "exp": time::current_time_in_secs() + 60 });
+
+        let jwt = keyring.encode(&claims, None).unwrap();
+        assert_eq!(jsonwebtoken::decode_header(&jwt).unwrap().kid, None);
+        assert_eq!(keyring.decode::<Value>(&jwt, None, true).unwrap(), claims);
+    }
+
+    #[test]
+    fn refuses_keys_without_an_active_one() {
+        let current_time = time::current_time_in_secs();
+
+        assert!(
+            Keyring::new("secret", vec![key_config("next", current_time + 100, None)]).is_err()
+        );
+        assert!(Keyring::new(
+            "secret",
+            vec![key_config(
+                "old",
+                current_time - 100,
+                Some(current_time - 10)
+            )]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,11 @@
+use jsonwebtoken::Algorithm;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeyConfig {
+    pub kid: String,
+    pub alg: Algorithm,
+    pub private_key: String,
+    pub activates_at: i64,
+    pub retires_at: Option<i64>,
+}
@@ -0,0 +1,2 @@
+pub mod key_config;
+pub mod signing_key;
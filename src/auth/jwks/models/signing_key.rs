@@ -0,0 +1,110 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+    Algorithm, DecodingKey, EncodingKey,
+};
+use openssl::pkey::PKey;
+
+use crate::{app::models::app_error::AppError, auth::config::JWT_EXP};
+
+use super::key_config::KeyConfig;
+
+pub struct SigningKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    pub encoding_key: EncodingKey,
+    pub decoding_key: DecodingKey,
+    pub jwk: Jwk,
+    pub activates_at: i64,
+    pub retires_at: Option<i64>,
+}
+
+impl SigningKey {
+    pub fn new(config: KeyConfig) -> Result<Self, AppError> {
+        let Ok(pkey) = PKey::private_key_from_pem(config.private_key.as_bytes()) else {
+            return Err(AppError::new("failed to parse jwt private key"));
+        };
+
+        let (encoding_key, key_algorithm, algorithm_parameters) = match config.alg {
+            Algorithm::RS256 => {
+                let Ok(rsa) = pkey.rsa() else {
+                    return Err(AppError::new("jwt private key is not an rsa key"));
+                };
+                let Ok(encoding_key) = EncodingKey::from_rsa_pem(config.private_key.as_bytes())
+                else {
+                    return Err(AppError::new("failed to encode jwt private key"));
+                };
+
+                (
+                    encoding_key,
+                    KeyAlgorithm::RS256,
+                    AlgorithmParameters::RSA(RSAKeyParameters {
+                        key_type: RSAKeyType::RSA,
+                        n: URL_SAFE_NO_PAD.encode(rsa.n().to_vec()),
+                        e: URL_SAFE_NO_PAD.encode(rsa.e().to_vec()),
+                    }),
+                )
+            }
+            Algorithm::EdDSA => {
+                let Ok(public_key) = pkey.raw_public_key() else {
+                    return Err(AppError::new("jwt private key is not an ed25519 key"));
+                };
+                let Ok(encoding_key) = EncodingKey::from_ed_pem(config.private_key.as_bytes())
+                else {
+                    return Err(AppError::new("failed to encode jwt private key"));
+                };
+
+                (
+                    encoding_key,
+                    KeyAlgorithm::EdDSA,
+                    AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                        key_type: OctetKeyPairType::OctetKeyPair,
+                        curve: EllipticCurve::Ed25519,
+                        x: URL_SAFE_NO_PAD.encode(public_key),
+                    }),
+                )
+            }
+            _ => return Err(AppError::new("unsupported jwt key algorithm")),
+        };
+
+        let jwk = Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_algorithm: Some(key_algorithm),
+                key_id: Some(config.kid.to_string()),
+                ..Default::default()
+            },
+            algorithm: algorithm_parameters,
+        };
+        let Ok(decoding_key) = DecodingKey::from_jwk(&jwk) else {
+            return Err(AppError::new("failed to create jwt decoding key"));
+        };
+
+        Ok(Self {
+            kid: config.kid,
+            algorithm: config.alg,
+            encoding_key,
+            decoding_key,
+            jwk,
+            activates_at: config.activates_at,
+            retires_at: config.retires_at,
+        })
+    }
+
+    pub fn signable(&self, current_time_in_secs: i64) -> bool {
+        self.activates_at <= current_time_in_secs
+            && self
+                .retires_at
+                .is_none_or(|retires_at| current_time_in_secs < retires_at)
+    }
+
+    // keys are published before they activate and stay verifiable until
+    // the last token they signed has expired
+    pub fn verifiable(&self, current_time_in_secs: i64) -> bool {
+        self.retires_at
+            .is_none_or(|retires_at| current_time_in_secs < retires_at + JWT_EXP as i64)
+    }
+}
@@ -4,6 +4,11 @@ pub mod config;
 pub mod controller;
 pub mod dtos;
 pub mod enums;
+pub mod google;
+pub mod jwks;
 pub mod models;
+pub mod oidc;
+pub mod password_policy;
+pub mod providers;
 pub mod service;
 pub mod util;
@@ -4,65 +4,72 @@ use axum::{
     http::{header::AUTHORIZATION, request::Parts, HeaderMap, StatusCode},
     RequestPartsExt,
 };
-use jsonwebtoken::{
-    encode, errors::ErrorKind, Algorithm, DecodingKey, EncodingKey, Header, Validation,
-};
+use jsonwebtoken::errors::ErrorKind;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::{
+    api_keys,
     app::{
         models::{api_error::ApiError, app_error::AppError},
         util::time,
     },
-    auth::{config::JWT_EXP, enums::pepper_type::PepperType},
+    auth::{
+        config::JWT_EXP,
+        enums::{pepper_type::PepperType, token_type::TokenType},
+        jwks::keyring::Keyring,
+        util::api_key,
+    },
     AppState,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccessTokenClaims {
-    pub id: String,
+    pub sub: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    pub typ: String,
+    pub scope: String,
+    pub jti: String,
     pub iat: u64,
     pub exp: u64,
 }
 
 impl AccessTokenClaims {
-    pub fn new(id: &str) -> Self {
+    pub fn new(sub: &str, sid: Option<&str>, typ: &str, scopes: &[&str]) -> Self {
         let iat = time::current_time_in_secs() as u64;
         let exp = iat + JWT_EXP;
 
         Self {
-            id: id.to_string(),
+            sub: sub.to_string(),
+            sid: sid.map(|sid| sid.to_string()),
+            typ: typ.to_string(),
+            scope: scopes.join(" "),
+            jti: Uuid::new_v4().to_string(),
             iat,
             exp,
         }
     }
 
-    pub fn to_jwt(self, secret: &str, pepper: Option<&str>) -> Result<String, AppError> {
-        let mut secret = secret.to_string();
-        if let Some(pepper) = pepper {
-            secret = [&secret, pepper].concat();
-        }
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scope.split(' ').any(|value| value == scope)
+    }
 
-        let encode_result = encode(
-            &Header::default(),
-            &self,
-            &EncodingKey::from_secret(secret.as_ref()),
-        );
-
-        match encode_result {
-            Ok(jwt) => Ok(jwt),
-            Err(e) => {
-                tracing::error!(%e);
-                Err(AppError::new("failed to encode claims"))
-            }
+    pub fn require_scope(&self, scope: &str) -> Result<(), ApiError> {
+        match self.has_scope(scope) {
+            true => Ok(()),
+            false => Err(ApiError::new(
+                StatusCode::FORBIDDEN,
+                &format!("Missing required scope {}.", scope),
+            )),
         }
     }
 
-    fn from_headers(
-        headers: &HeaderMap,
-        secret: &str,
-        pepper: Option<&str>,
-    ) -> Result<Self, ApiError> {
+    pub fn to_jwt(self, keyring: &Keyring, pepper: Option<&str>) -> Result<String, AppError> {
+        keyring.encode(&self, pepper)
+    }
+
+    pub fn bearer_from_headers(headers: &HeaderMap) -> Result<&str, ApiError> {
         let Some(header_value) = headers.get(AUTHORIZATION) else {
             return Err(ApiError::new(
                 StatusCode::UNAUTHORIZED,
@@ -85,29 +92,45 @@ impl AccessTokenClaims {
             ));
         }
 
-        AccessTokenClaims::from_jwt(split[1], secret, pepper, true)
+        Ok(split[1])
     }
 
-    pub fn from_jwt(
+    fn from_headers(
+        headers: &HeaderMap,
+        keyring: &Keyring,
+        pepper: Option<&str>,
+        typ: &str,
+    ) -> Result<Self, ApiError> {
+        let bearer = AccessTokenClaims::bearer_from_headers(headers)?;
+
+        AccessTokenClaims::from_typed_jwt(bearer, keyring, pepper, typ)
+    }
+
+    pub fn from_typed_jwt(
         jwt: &str,
-        secret: &str,
+        keyring: &Keyring,
         pepper: Option<&str>,
-        validate_exp: bool,
+        typ: &str,
     ) -> Result<Self, ApiError> {
-        let mut secret = secret.to_string();
-        if let Some(pepper) = pepper {
-            secret = [&secret, pepper].concat();
+        let claims = AccessTokenClaims::from_jwt(jwt, keyring, pepper, true)?;
+        if claims.typ != typ {
+            return Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "Invalid token type.",
+            ));
         }
 
-        let decoding_key = DecodingKey::from_secret(secret.as_bytes());
-        let mut validation = Validation::new(Algorithm::HS256);
-        validation.validate_exp = validate_exp;
-
-        let decode_result =
-            jsonwebtoken::decode::<AccessTokenClaims>(&jwt, &decoding_key, &validation);
+        Ok(claims)
+    }
 
-        match decode_result {
-            Ok(data) => Ok(data.claims),
+    pub fn from_jwt(
+        jwt: &str,
+        keyring: &Keyring,
+        pepper: Option<&str>,
+        validate_exp: bool,
+    ) -> Result<Self, ApiError> {
+        match keyring.decode::<AccessTokenClaims>(jwt, pepper, validate_exp) {
+            Ok(claims) => Ok(claims),
             Err(e) => match e.kind() {
                 ErrorKind::ExpiredSignature => {
                     Err(ApiError::new(StatusCode::UNAUTHORIZED, "Token expired."))
@@ -132,7 +155,18 @@ where
         let state = parts.extract_with_state::<AppState, _>(state).await?;
         let headers = &parts.headers;
 
-        match AccessTokenClaims::from_headers(headers, &state.envy.jwt_secret, None) {
+        let bearer = AccessTokenClaims::bearer_from_headers(headers)?;
+        if api_key::is_api_key(bearer) {
+            let api_key = api_keys::service::use_api_key(bearer, &state).await?;
+            return Ok(ExtractClaims(api_key.to_claims()));
+        }
+
+        match AccessTokenClaims::from_headers(
+            headers,
+            state.authman.keyring(),
+            None,
+            TokenType::ACCESS,
+        ) {
             Ok(claims) => Ok(ExtractClaims(claims)),
             Err(e) => Err(e),
         }
@@ -155,8 +189,9 @@ where
 
         match AccessTokenClaims::from_headers(
             headers,
-            &state.envy.jwt_secret,
+            state.authman.keyring(),
             Some(PepperType::VERIFY_EMAIL),
+            TokenType::VERIFY_EMAIL,
         ) {
             Ok(claims) => Ok(ExtractClaimsPepperVerifyEmail(claims)),
             Err(e) => Err(e),
@@ -180,8 +215,9 @@ where
 
         match AccessTokenClaims::from_headers(
             headers,
-            &state.envy.jwt_secret,
+            state.authman.keyring(),
             Some(PepperType::EDIT_EMAIL),
+            TokenType::EDIT_EMAIL,
         ) {
             Ok(claims) => Ok(ExtractClaimsPepperEditEmail(claims)),
             Err(e) => Err(e),
@@ -205,11 +241,64 @@ where
 
         match AccessTokenClaims::from_headers(
             headers,
-            &state.envy.jwt_secret,
+            state.authman.keyring(),
             Some(PepperType::EDIT_PASSWORD),
+            TokenType::EDIT_PASSWORD,
         ) {
             Ok(claims) => Ok(ExtractClaimsPepperEditPassword(claims)),
             Err(e) => Err(e),
         }
     }
 }
+
+pub struct ExtractClaimsPepperSigninChallenge(pub AccessTokenClaims);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ExtractClaimsPepperSigninChallenge
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let state = parts.extract_with_state::<AppState, _>(state).await?;
+        let headers = &parts.headers;
+
+        match AccessTokenClaims::from_headers(
+            headers,
+            state.authman.keyring(),
+            Some(PepperType::SIGNIN_CHALLENGE),
+            TokenType::SIGNIN_CHALLENGE,
+        ) {
+            Ok(claims) => Ok(ExtractClaimsPepperSigninChallenge(claims)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+pub struct ExtractClaimsPepperUnsubscribeDigest(pub AccessTokenClaims);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ExtractClaimsPepperUnsubscribeDigest
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let state = parts.extract_with_state::<AppState, _>(state).await?;
+        let headers = &parts.headers;
+
+        match AccessTokenClaims::from_headers(
+            headers,
+            state.authman.keyring(),
+            Some(PepperType::UNSUBSCRIBE_DIGEST),
+            TokenType::UNSUBSCRIBE_DIGEST,
+        ) {
+            Ok(claims) => Ok(ExtractClaimsPepperUnsubscribeDigest(claims)),
+            Err(e) => Err(e),
+        }
+    }
+}
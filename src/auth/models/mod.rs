@@ -1,2 +1,6 @@
 pub mod access_info;
 pub mod access_token_claims;
+pub mod recovery_codes;
+pub mod signin_challenge;
+pub mod signin_response;
+pub mod totp_enrollment;
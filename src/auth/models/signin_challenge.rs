@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SigninChallenge {
+    pub challenge_token: String,
+    pub challenge_type: String,
+}
@@ -0,0 +1,6 @@
+use super::{access_info::AccessInfo, signin_challenge::SigninChallenge};
+
+pub enum SigninResponse {
+    Access(AccessInfo),
+    Challenge(SigninChallenge),
+}
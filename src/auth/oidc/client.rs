@@ -0,0 +1,163 @@
+use std::{collections::HashMap, time::Instant};
+
+use axum::http::StatusCode;
+use jsonwebtoken::{jwk::JwkSet, Algorithm, DecodingKey, Validation};
+
+use crate::app::models::{api_error::ApiError, app_error::AppError};
+
+use super::{
+    models::{id_token_claims::IdTokenClaims, provider_config::ProviderConfig},
+    responses::{discovery_res::DiscoveryResponse, token_res::TokenResponse},
+};
+
+#[derive(Debug, Clone)]
+pub struct OidcClient {
+    pub config: ProviderConfig,
+    token_endpoint: Option<String>,
+    public_keys: JwkSet,
+    refreshed_at: Instant,
+}
+
+impl OidcClient {
+    pub fn new(config: ProviderConfig) -> Self {
+        Self {
+            config,
+            token_endpoint: None,
+            public_keys: JwkSet { keys: Vec::new() },
+            refreshed_at: Instant::now(),
+        }
+    }
+
+    pub fn expired(&self) -> bool {
+        self.refreshed_at.elapsed().as_secs() > 3600
+    }
+
+    pub async fn login(&mut self, http_client: &reqwest::Client) -> Result<(), AppError> {
+        let discovery_url = format!(
+            "{}/.well-known/openid-configuration",
+            self.config.issuer.trim_end_matches('/')
+        );
+
+        let Ok(response) = http_client.get(discovery_url).send().await else {
+            return Err(AppError::new("failed to get openid configuration"));
+        };
+        let Ok(text) = response.text().await else {
+            return Err(AppError::new("failed to get response text"));
+        };
+        let Ok(discovery_res) = serde_json::from_str::<DiscoveryResponse>(&text) else {
+            return Err(AppError::new(
+                "failed to decode openid configuration from text",
+            ));
+        };
+        if discovery_res.issuer != self.config.issuer {
+            return Err(AppError::new("openid configuration issuer does not match"));
+        }
+
+        let Ok(response) = http_client.get(&discovery_res.jwks_uri).send().await else {
+            return Err(AppError::new("failed to get oidc public keys"));
+        };
+        let Ok(text) = response.text().await else {
+            return Err(AppError::new("failed to get response text"));
+        };
+        let Ok(public_keys) = serde_json::from_str::<JwkSet>(&text) else {
+            return Err(AppError::new("failed to decode public keys from text"));
+        };
+
+        self.token_endpoint = discovery_res.token_endpoint;
+        self.public_keys = public_keys;
+        self.refreshed_at = Instant::now();
+
+        Ok(())
+    }
+
+    pub async fn exchange_auth_code(
+        &self,
+        auth_code: &str,
+        http_client: &reqwest::Client,
+    ) -> Result<String, ApiError> {
+        let Some(token_endpoint) = &self.token_endpoint else {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "Provider does not support auth codes.",
+            ));
+        };
+
+        let mut form = HashMap::new();
+        form.insert("client_id", self.config.client_id.to_string());
+        form.insert("code", auth_code.to_string());
+        form.insert("grant_type", "authorization_code".to_string());
+        if let Some(client_secret) = &self.config.client_secret {
+            form.insert("client_secret", client_secret.to_string());
+        }
+        if let Some(redirect_uri) = &self.config.redirect_uri {
+            form.insert("redirect_uri", redirect_uri.to_string());
+        }
+
+        let result = http_client.post(token_endpoint).form(&form).send().await;
+
+        match result {
+            Ok(res) => match res.text().await {
+                Ok(text) => match serde_json::from_str::<TokenResponse>(&text) {
+                    Ok(res) => Ok(res.id_token),
+                    Err(_) => {
+                        tracing::error!(%text);
+                        Err(ApiError::new(
+                            StatusCode::UNAUTHORIZED,
+                            "Failed to authorize auth code.",
+                        ))
+                    }
+                },
+                Err(e) => {
+                    tracing::error!(%e);
+                    Err(ApiError::new(
+                        StatusCode::UNAUTHORIZED,
+                        "Failed to authorize auth code.",
+                    ))
+                }
+            },
+            Err(e) => {
+                tracing::error!(%e);
+                Err(ApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to request authorization.",
+                ))
+            }
+        }
+    }
+
+    pub fn decode_id_token(&self, id_token: &str) -> Result<IdTokenClaims, AppError> {
+        let Ok(id_token_header) = jsonwebtoken::decode_header(id_token) else {
+            return Err(AppError::new("failed to decode id_token header"));
+        };
+        if matches!(
+            id_token_header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(AppError::new("id_token is not asymmetrically signed"));
+        }
+
+        let Some(kid) = id_token_header.kid else {
+            return Err(AppError::new("id_token_header has no kid"));
+        };
+
+        let Some(public_key) = self.public_keys.find(&kid) else {
+            return Err(AppError::new("no public key matched id_token"));
+        };
+
+        let Ok(decoding_key) = DecodingKey::from_jwk(public_key) else {
+            return Err(AppError::new("failed to create decoding key"));
+        };
+
+        let mut validation = Validation::new(id_token_header.alg);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_issuer(&[&self.config.issuer]);
+
+        match jsonwebtoken::decode::<IdTokenClaims>(id_token, &decoding_key, &validation) {
+            Ok(data) => Ok(data.claims),
+            Err(e) => {
+                tracing::error!(%e);
+                Err(AppError::new("failed to decode id_token"))
+            }
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdTokenClaims {
+    #[serde(rename(deserialize = "sub"))]
+    pub sub: String,
+    #[serde(rename(deserialize = "email"))]
+    pub email: Option<String>,
+    #[serde(rename(deserialize = "email_verified"), default)]
+    pub email_verified: bool,
+}
@@ -0,0 +1,2 @@
+pub mod id_token_claims;
+pub mod provider_config;
@@ -0,0 +1,83 @@
+use axum::{async_trait, http::StatusCode};
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+use crate::{
+    app::models::api_error::ApiError,
+    auth::{
+        dtos::provider_credential_dto::ProviderCredentialDto,
+        providers::{
+            identity_provider::IdentityProvider, models::verified_identity::VerifiedIdentity,
+        },
+    },
+};
+
+use super::client::OidcClient;
+
+#[derive(Debug)]
+pub struct OidcProvider {
+    name: String,
+    client: RwLock<OidcClient>,
+}
+
+impl OidcProvider {
+    pub fn new(client: OidcClient) -> Self {
+        Self {
+            name: client.config.name.to_string(),
+            client: RwLock::new(client),
+        }
+    }
+
+    async fn client(&self, http_client: &reqwest::Client) -> RwLockReadGuard<'_, OidcClient> {
+        let readable_oidc_client = self.client.read().await;
+        if readable_oidc_client.expired() {
+            drop(readable_oidc_client);
+            let mut writable_oidc_client = self.client.write().await;
+            let _ = writable_oidc_client.login(http_client).await;
+        }
+
+        self.client.read().await
+    }
+}
+
+#[async_trait]
+impl IdentityProvider for OidcProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn verify(
+        &self,
+        dto: &ProviderCredentialDto,
+        http_client: &reqwest::Client,
+    ) -> Result<VerifiedIdentity, ApiError> {
+        let oidc_client = self.client(http_client).await;
+
+        let id_token = match (&dto.id_token, &dto.auth_code) {
+            (Some(id_token), _) => id_token.to_string(),
+            (None, Some(auth_code)) => {
+                oidc_client
+                    .exchange_auth_code(auth_code, http_client)
+                    .await?
+            }
+            (None, None) => {
+                return Err(ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    "Missing id_token or auth_code.",
+                ))
+            }
+        };
+
+        let Ok(claims) = oidc_client.decode_id_token(&id_token) else {
+            return Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "Failed to verify id token.",
+            ));
+        };
+
+        Ok(VerifiedIdentity {
+            subject: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified,
+        })
+    }
+}
@@ -0,0 +1,11 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct DiscoveryResponse {
+    #[serde(rename(deserialize = "issuer"))]
+    pub issuer: String,
+    #[serde(rename(deserialize = "jwks_uri"))]
+    pub jwks_uri: String,
+    #[serde(rename(deserialize = "token_endpoint"))]
+    pub token_endpoint: Option<String>,
+}
@@ -0,0 +1,2 @@
+pub mod discovery_res;
+pub mod token_res;
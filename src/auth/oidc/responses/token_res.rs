@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct TokenResponse {
+    #[serde(rename(deserialize = "id_token"))]
+    pub id_token: String,
+}
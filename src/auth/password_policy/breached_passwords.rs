@@ -0,0 +1,74 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use openssl::sha::sha1;
+
+use crate::app::models::app_error::AppError;
+
+static PREFIX_LEN: usize = 5;
+
+// a directory with one file per sha-1 prefix, e.g. 21BD1.txt holding "SUFFIX:count" lines,
+// the same layout as the pwned passwords range api and its downloader, so only the
+// shard of the password being checked is ever read
+#[derive(Debug, Default)]
+pub struct BreachedPasswords {
+    dir: Option<PathBuf>,
+}
+
+impl BreachedPasswords {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(path: &str) -> Result<Self, AppError> {
+        if !Path::new(path).is_dir() {
+            return Err(AppError::new("breached passwords path is not a directory"));
+        }
+
+        Ok(Self {
+            dir: Some(PathBuf::from(path)),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match &self.dir {
+            Some(dir) => match fs::read_dir(dir) {
+                Ok(mut entries) => entries.next().is_none(),
+                Err(_) => true,
+            },
+            None => true,
+        }
+    }
+
+    pub async fn contains(&self, password: &str) -> bool {
+        let Some(dir) = &self.dir else {
+            return false;
+        };
+
+        let hash: String = sha1(password.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect();
+        let (prefix, suffix) = hash.split_at(PREFIX_LEN);
+
+        let shard = match tokio::fs::read_to_string(dir.join(format!("{}.txt", prefix))).await {
+            Ok(shard) => shard,
+            Err(e) => {
+                if e.kind() != io::ErrorKind::NotFound {
+                    tracing::error!(%e);
+                }
+                return false;
+            }
+        };
+
+        shard.lines().any(|line| {
+            line.split(':')
+                .next()
+                .unwrap_or("")
+                .trim()
+                .eq_ignore_ascii_case(suffix)
+        })
+    }
+}
@@ -0,0 +1,2 @@
+pub mod breached_passwords;
+pub mod models;
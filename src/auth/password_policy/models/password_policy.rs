@@ -0,0 +1,64 @@
+use axum::http::StatusCode;
+
+use crate::{
+    app::models::api_error::ApiError, auth::password_policy::breached_passwords::BreachedPasswords,
+};
+
+static MIN_USER_INPUT_LEN: usize = 3;
+
+#[derive(Debug)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub min_score: u8,
+    pub breached_passwords: BreachedPasswords,
+}
+
+impl PasswordPolicy {
+    pub fn new(min_length: usize, min_score: u8, breached_passwords: BreachedPasswords) -> Self {
+        Self {
+            min_length,
+            min_score,
+            breached_passwords,
+        }
+    }
+
+    pub async fn check(&self, password: &str, username: &str, email: &str) -> Result<(), ApiError> {
+        if password.chars().count() < self.min_length {
+            return Err(ApiError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                &format!("password must be at least {} characters.", self.min_length),
+            ));
+        }
+
+        let lowercase = password.to_lowercase();
+        let email_name = email.split('@').next().unwrap_or("");
+        let includes_user_input = [username, email_name].iter().any(|user_input| {
+            user_input.len() >= MIN_USER_INPUT_LEN && lowercase.contains(&user_input.to_lowercase())
+        });
+        if includes_user_input {
+            return Err(ApiError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "password must not contain your username or email.",
+            ));
+        }
+
+        let score: u8 = zxcvbn::zxcvbn(password, &[username, email_name])
+            .score()
+            .into();
+        if score < self.min_score {
+            return Err(ApiError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "password is too easy to guess. Try a longer passphrase with uncommon words.",
+            ));
+        }
+
+        if self.breached_passwords.contains(password).await {
+            return Err(ApiError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "password has appeared in a data breach. Choose a different password.",
+            ));
+        }
+
+        Ok(())
+    }
+}
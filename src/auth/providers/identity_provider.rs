@@ -0,0 +1,20 @@
+use std::fmt::Debug;
+
+use axum::async_trait;
+
+use crate::{
+    app::models::api_error::ApiError, auth::dtos::provider_credential_dto::ProviderCredentialDto,
+};
+
+use super::models::verified_identity::VerifiedIdentity;
+
+#[async_trait]
+pub trait IdentityProvider: Debug + Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn verify(
+        &self,
+        dto: &ProviderCredentialDto,
+        http_client: &reqwest::Client,
+    ) -> Result<VerifiedIdentity, ApiError>;
+}
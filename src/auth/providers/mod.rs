@@ -0,0 +1,2 @@
+pub mod identity_provider;
+pub mod models;
@@ -0,0 +1 @@
+pub mod verified_identity;
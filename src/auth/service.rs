@@ -1,126 +1,568 @@
 use axum::http::StatusCode;
+use jsonwebtoken::jwk::JwkSet;
 
 use crate::{
     app::models::api_error::ApiError,
     devices,
+    identities::{self, models::user_identity::UserIdentity},
+    magic_links,
     mail::{
         self,
-        templates::{request_email_update_template, request_password_update_template},
+        templates::{
+            account_exists_template, magic_link_template, refresh_token_reuse_template,
+            request_email_update_template, request_password_update_template,
+            signin_failures_template, welcome_template,
+        },
     },
+    passkeys::{self, models::passkey_options::PasskeyOptions},
+    recovery_codes, signin_attempts,
     users::{self, models::user::User},
     AppState,
 };
 
 use super::{
+    config::{
+        SIGNIN_ACCOUNT_FREE_ATTEMPTS, SIGNIN_ALERT_THRESHOLD, SIGNIN_CHALLENGE_EXP,
+        SIGNIN_IP_FREE_ATTEMPTS,
+    },
     dtos::{
-        edit_password_dto::EditPasswordDto, refresh_access_info_dto::RefreshAccessInfoDto,
+        edit_password_dto::EditPasswordDto, provider_credential_dto::ProviderCredentialDto,
+        refresh_access_info_dto::RefreshAccessInfoDto,
         request_email_update_dto::RequestEmailUpdateDto,
-        request_password_update_dto::RequestPasswordUpdateDto, signin_apple_dto::SigninAppleDto,
-        signin_dto::SigninDto, signout_dto::SignoutDto, signup_dto::SignupDto,
+        request_magic_link_dto::RequestMagicLinkDto,
+        request_password_update_dto::RequestPasswordUpdateDto, signin_dto::SigninDto,
+        signin_magic_link_dto::SigninMagicLinkDto, signin_passkey_dto::SigninPasskeyDto,
+        signout_dto::SignoutDto, signup_dto::SignupDto, totp_code_dto::TotpCodeDto,
+    },
+    enums::{
+        pepper_type::PepperType, scope::Scope, token_type::TokenType,
+        webauthn_ceremony::WebauthnCeremony,
     },
-    enums::pepper_type::PepperType,
-    models::{access_info::AccessInfo, access_token_claims::AccessTokenClaims},
-    util::password,
+    models::{
+        access_info::AccessInfo, access_token_claims::AccessTokenClaims,
+        recovery_codes::RecoveryCodes, signin_challenge::SigninChallenge,
+        signin_response::SigninResponse, totp_enrollment::TotpEnrollment,
+    },
+    util::{password, recovery_code, totp, webauthn},
 };
 
-pub async fn signup(dto: &SignupDto, state: &AppState) -> Result<AccessInfo, ApiError> {
-    let Ok(password_hash) = password::hash(dto.password.to_string()).await else {
+pub async fn get_jwks(state: &AppState) -> Result<JwkSet, ApiError> {
+    Ok(state.authman.keyring().jwks())
+}
+
+// responds the same whether or not the email is already registered
+pub async fn signup(dto: &SignupDto, state: &AppState) -> Result<(), ApiError> {
+    if let Some(username) = &dto.username {
+        if users::util::username::is_reserved(username) {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "Username is reserved.",
+            ));
+        }
+
+        // checked before and apart from the email so a taken username never reveals it
+        match users::service::get_user_by_username(username, state).await {
+            Ok(_) => {
+                return Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    "Username already exists.",
+                ))
+            }
+            Err(e) => match e.code {
+                StatusCode::NOT_FOUND => (),
+                _ => return Err(e),
+            },
+        }
+    }
+
+    state
+        .authman
+        .password_policy()
+        .check(
+            &dto.password,
+            dto.username.as_deref().unwrap_or(""),
+            &dto.email,
+        )
+        .await?;
+
+    let Ok(password_hash) = password::hash(
+        dto.password.to_string(),
+        state.authman.argon2_params().clone(),
+    )
+    .await
+    else {
         return Err(ApiError::internal_server_error());
     };
 
-    let user = User::new(&dto.username, &dto.email, &Some(password_hash), &None);
+    let user = User::new(&dto.username, &dto.email, &Some(password_hash));
+    let envy = state.envy.clone();
 
     match users::service::create_user(user, state).await {
-        Ok(user) => signin_user(&user, state).await,
-        Err(e) => Err(e),
+        Ok(user) => {
+            let magic_link =
+                magic_links::service::create_magic_link(&user.id.to_string(), state).await?;
+
+            tokio::spawn(async move {
+                let mail_template = welcome_template::new(&magic_link.token);
+                let _ = mail::service::send(&user.email, &mail_template.0, &mail_template.1, &envy)
+                    .await;
+            });
+        }
+        Err(e) => match e.code {
+            StatusCode::CONFLICT => {
+                // without a user for the email, the username was taken since the check above
+                let Ok(user) = users::service::get_user_by_email(&dto.email, state).await else {
+                    return Err(ApiError::new(
+                        StatusCode::CONFLICT,
+                        "Username already exists.",
+                    ));
+                };
+
+                tokio::spawn(async move {
+                    let mail_template = account_exists_template::new();
+                    let _ =
+                        mail::service::send(&user.email, &mail_template.0, &mail_template.1, &envy)
+                            .await;
+                });
+            }
+            _ => return Err(e),
+        },
     }
+
+    Ok(())
 }
 
-async fn signup_apple(
-    email: &str,
-    id_apple: &str,
+pub async fn signin(
+    dto: &SigninDto,
+    client_ip: &str,
     state: &AppState,
-) -> Result<AccessInfo, ApiError> {
-    let user = User::new(&None, email, &None, &Some(id_apple.to_string()));
+) -> Result<SigninResponse, ApiError> {
+    let user = match users::service::get_user_by_signin_dto(dto, state).await {
+        Ok(user) => Some(user),
+        Err(e) => match e.code {
+            StatusCode::NOT_FOUND => None,
+            _ => return Err(e),
+        },
+    };
 
-    match users::service::create_user(user, state).await {
-        Ok(user) => signin_user(&user, state).await,
-        Err(e) => Err(e),
+    let account_key = match (&user, &dto.username, &dto.email) {
+        (Some(user), _, _) => signin_attempts::service::account_key(&user.id.to_string()),
+        (None, Some(identifier), _) | (None, None, Some(identifier)) => {
+            signin_attempts::service::account_key(identifier)
+        }
+        (None, None, None) => return Err(ApiError::internal_server_error()),
+    };
+    let ip_key = signin_attempts::service::ip_key(client_ip);
+    signin_attempts::service::check_signin_attempts(&[account_key.clone(), ip_key.clone()], state)
+        .await?;
+    signin_attempts::service::reserve_signin_attempt(&ip_key, SIGNIN_IP_FREE_ATTEMPTS, state)
+        .await?;
+    let signin_attempt = signin_attempts::service::reserve_signin_attempt(
+        &account_key,
+        SIGNIN_ACCOUNT_FREE_ATTEMPTS,
+        state,
+    )
+    .await?;
+
+    let verify_result = match user.as_ref().and_then(|user| user.password.clone()) {
+        Some(user_password) => password::verify(dto.password.to_string(), user_password).await,
+        None => password::verify_dummy(
+            dto.password.to_string(),
+            state.authman.argon2_params().clone(),
+        )
+        .await
+        .map(|_| false),
+    };
+    let Ok(matches) = verify_result else {
+        return Err(ApiError::internal_server_error());
+    };
+
+    let user = match (user, matches) {
+        (Some(user), true) => user,
+        (user, _) => {
+            if let Some(user) = user {
+                if signin_attempt.failures >= SIGNIN_ALERT_THRESHOLD
+                    && signin_attempts::service::mark_signin_alerted(&account_key, state).await?
+                {
+                    let envy = state.envy.clone();
+
+                    tokio::spawn(async move {
+                        let mail_template = signin_failures_template::new();
+                        let _ = mail::service::send(
+                            &user.email,
+                            &mail_template.0,
+                            &mail_template.1,
+                            &envy,
+                        )
+                        .await;
+                    });
+                }
+            }
+
+            return Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "Invalid credentials.",
+            ));
+        }
+    };
+
+    signin_attempts::service::release_signin_attempt(&ip_key, SIGNIN_IP_FREE_ATTEMPTS, state)
+        .await?;
+    signin_attempts::service::clear_signin_attempts(&account_key, state).await?;
+
+    if let Some(user_password) = &user.password {
+        if password::needs_rehash(user_password, state.authman.argon2_params()) {
+            let id = user.id.to_string();
+            let password = dto.password.to_string();
+            let old_password_hash = user_password.to_string();
+            let state = state.clone();
+
+            tokio::spawn(async move {
+                let params = state.authman.argon2_params().clone();
+                let Ok(new_password_hash) = password::hash(password, params).await else {
+                    return;
+                };
+                let _ = users::service::rehash_user_password(
+                    &id,
+                    &old_password_hash,
+                    &new_password_hash,
+                    &state,
+                )
+                .await;
+            });
+        }
+    }
+
+    signin_first_factor(&user, state).await
+}
+
+async fn signin_first_factor(user: &User, state: &AppState) -> Result<SigninResponse, ApiError> {
+    if user.totp_enabled {
+        let mut claims =
+            AccessTokenClaims::new(&user.id.to_string(), None, TokenType::SIGNIN_CHALLENGE, &[]);
+        claims.exp = claims.iat + SIGNIN_CHALLENGE_EXP;
+        let Ok(challenge_token) =
+            claims.to_jwt(state.authman.keyring(), Some(PepperType::SIGNIN_CHALLENGE))
+        else {
+            return Err(ApiError::internal_server_error());
+        };
+
+        return Ok(SigninResponse::Challenge(SigninChallenge {
+            challenge_token,
+            challenge_type: "totp".to_string(),
+        }));
     }
+
+    Ok(SigninResponse::Access(signin_user(user, state).await?))
 }
 
-pub async fn signin(dto: &SigninDto, state: &AppState) -> Result<AccessInfo, ApiError> {
-    let user_result = users::service::get_user_by_signin_dto(dto, state).await;
-    let Ok(user) = user_result else {
-        // TODO: return sleep time error
+pub async fn request_magic_link(
+    dto: &RequestMagicLinkDto,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let user = match users::service::get_user_by_email(&dto.email, state).await {
+        Ok(user) => user,
+        Err(e) => match e.code {
+            StatusCode::NOT_FOUND => return Ok(()),
+            _ => return Err(e),
+        },
+    };
+
+    let magic_link =
+        match magic_links::service::create_magic_link(&user.id.to_string(), state).await {
+            Ok(magic_link) => magic_link,
+            Err(e) => match e.code {
+                StatusCode::TOO_MANY_REQUESTS => {
+                    tracing::warn!("too many sign-in links requested for user {}", user.id);
+                    return Ok(());
+                }
+                _ => return Err(e),
+            },
+        };
+
+    let envy = state.envy.clone();
+
+    tokio::spawn(async move {
+        let mail_template = magic_link_template::new(&magic_link.token);
+        let _ = mail::service::send(&user.email, &mail_template.0, &mail_template.1, &envy).await;
+    });
+
+    Ok(())
+}
+
+pub async fn signin_magic_link(
+    dto: &SigninMagicLinkDto,
+    state: &AppState,
+) -> Result<SigninResponse, ApiError> {
+    let magic_link = magic_links::service::consume_magic_link(&dto.token, state).await?;
+    let user = users::service::get_user_by_id(&magic_link.user_id.to_string(), state).await?;
+
+    signin_first_factor(&user, state).await
+}
+
+pub async fn signin_totp(
+    dto: &TotpCodeDto,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<AccessInfo, ApiError> {
+    let user = users::service::get_user_by_id(&claims.sub, state).await?;
+    if !user.totp_enabled {
         return Err(ApiError::new(
             StatusCode::UNAUTHORIZED,
             "Invalid credentials.",
         ));
+    }
+
+    let account_key = signin_attempts::service::account_key(&user.id.to_string());
+    signin_attempts::service::check_signin_attempts(std::slice::from_ref(&account_key), state)
+        .await?;
+    signin_attempts::service::reserve_signin_attempt(
+        &account_key,
+        SIGNIN_ACCOUNT_FREE_ATTEMPTS,
+        state,
+    )
+    .await?;
+
+    verify_second_factor(&user, &dto.code, state).await?;
+
+    signin_attempts::service::clear_signin_attempts(&account_key, state).await?;
+
+    signin_user(&user, state).await
+}
+
+async fn verify_second_factor(user: &User, code: &str, state: &AppState) -> Result<(), ApiError> {
+    let user_id = user.id.to_string();
+
+    if recovery_code::is_recovery_code(code) {
+        return recovery_codes::service::consume_recovery_code(&user_id, code, state).await;
+    }
+
+    let Some(totp_secret) = &user.totp_secret else {
+        return Err(ApiError::new(StatusCode::UNAUTHORIZED, "Invalid code."));
+    };
+    let Ok(step) = totp::verify(totp_secret, code) else {
+        return Err(ApiError::internal_server_error());
     };
-    let Some(user_password) = &user.password else {
+    let Some(step) = step else {
+        return Err(ApiError::new(StatusCode::UNAUTHORIZED, "Invalid code."));
+    };
+
+    users::service::edit_user_totp_last_used_step(&user_id, step, state).await
+}
+
+pub async fn request_totp(
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<TotpEnrollment, ApiError> {
+    let user = users::service::get_user_by_id(&claims.sub, state).await?;
+    if user.totp_enabled {
         return Err(ApiError::new(
-            StatusCode::UNAUTHORIZED,
-            "Invalid credentials.",
+            StatusCode::CONFLICT,
+            "Two-factor authentication is already enabled.",
         ));
+    }
+
+    let secret = totp::new_secret();
+    let Ok(otpauth_uri) = totp::otpauth_uri(&secret, &user.email) else {
+        return Err(ApiError::internal_server_error());
     };
-    let Ok(matches) = password::verify(dto.password.to_string(), user_password.to_string()).await
-    else {
-        // TODO: return sleep time error
+
+    users::service::edit_user_totp_secret(&claims.sub, &secret, state).await?;
+
+    Ok(TotpEnrollment {
+        secret,
+        otpauth_uri,
+    })
+}
+
+pub async fn enable_totp(
+    dto: &TotpCodeDto,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<RecoveryCodes, ApiError> {
+    let user = users::service::get_user_by_id(&claims.sub, state).await?;
+    if user.totp_enabled {
         return Err(ApiError::new(
-            StatusCode::UNAUTHORIZED,
-            "Invalid credentials.",
+            StatusCode::CONFLICT,
+            "Two-factor authentication is already enabled.",
+        ));
+    }
+    let Some(totp_secret) = &user.totp_secret else {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "Two-factor authentication was not requested.",
         ));
     };
-    if !matches {
-        // TODO: return sleep time error
+
+    let Ok(step) = totp::verify(totp_secret, &dto.code) else {
+        return Err(ApiError::internal_server_error());
+    };
+    let Some(step) = step else {
+        return Err(ApiError::new(StatusCode::UNAUTHORIZED, "Invalid code."));
+    };
+
+    users::service::enable_user_totp(&claims.sub, step, state).await?;
+    let recovery_codes =
+        recovery_codes::service::replace_recovery_codes(&claims.sub, state).await?;
+
+    Ok(RecoveryCodes { recovery_codes })
+}
+
+pub async fn disable_totp(
+    dto: &TotpCodeDto,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let user = users::service::get_user_by_id(&claims.sub, state).await?;
+    if !user.totp_enabled {
         return Err(ApiError::new(
-            StatusCode::UNAUTHORIZED,
-            "Invalid credentials.",
+            StatusCode::BAD_REQUEST,
+            "Two-factor authentication is not enabled.",
         ));
     }
 
-    signin_user(&user, state).await
+    verify_second_factor(&user, &dto.code, state).await?;
+
+    users::service::disable_user_totp(&claims.sub, state).await?;
+    recovery_codes::service::delete_recovery_codes(&claims.sub, state).await
 }
 
-pub async fn signin_apple(dto: &SigninAppleDto, state: &AppState) -> Result<AccessInfo, ApiError> {
-    let _apple_client = state.authman.apple_client(&state.http_client).await;
-    let apple_client = _apple_client.read().await;
-    let auth_code_res = apple_client
-        .validate_auth_code(
-            &dto.auth_code,
-            dto.client.clone().unwrap_or("ios".to_string()),
-            &state.http_client,
-        )
-        .await?;
+pub async fn regenerate_recovery_codes(
+    dto: &TotpCodeDto,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<RecoveryCodes, ApiError> {
+    let user = users::service::get_user_by_id(&claims.sub, state).await?;
+    if !user.totp_enabled {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "Two-factor authentication is not enabled.",
+        ));
+    }
+
+    verify_second_factor(&user, &dto.code, state).await?;
+
+    let recovery_codes =
+        recovery_codes::service::replace_recovery_codes(&claims.sub, state).await?;
+
+    Ok(RecoveryCodes { recovery_codes })
+}
+
+pub async fn request_passkey_signin(state: &AppState) -> Result<PasskeyOptions, ApiError> {
+    passkeys::service::request_passkey_authentication(state).await
+}
+
+pub async fn signin_passkey(
+    dto: &SigninPasskeyDto,
+    state: &AppState,
+) -> Result<AccessInfo, ApiError> {
+    let challenge = passkeys::service::consume_webauthn_challenge(
+        &dto.challenge_id,
+        WebauthnCeremony::AUTHENTICATION,
+        state,
+    )
+    .await?;
 
-    let client_id = match dto.client.clone().unwrap_or("ios".to_string()).as_ref() {
-        "ios" => apple_client.config.client_id_ios.to_string(),
-        "android" => apple_client.config.client_id_android.to_string(),
-        "web" => apple_client.config.client_id_web.to_string(),
-        _ => apple_client.config.client_id_ios.to_string(),
+    let Ok(passkey) =
+        passkeys::service::get_passkey_by_credential_id(&dto.credential_id, state).await
+    else {
+        return Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "Invalid credentials.",
+        ));
     };
 
-    let Ok(claims) = auth_code_res.decode_id_token(&apple_client.public_keys, &client_id) else {
-        return Err(ApiError::internal_server_error());
+    let sign_count = match webauthn::verify_assertion(
+        &dto.client_data_json,
+        &dto.authenticator_data,
+        &dto.signature,
+        &challenge.challenge,
+        &passkey.public_key,
+        passkey.sign_count,
+    ) {
+        Ok(sign_count) => sign_count,
+        Err(e) => {
+            tracing::warn!("passkey {}: {}", passkey.id, e.message);
+            return Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "Invalid credentials.",
+            ));
+        }
     };
+    passkeys::service::edit_passkey_sign_count(&passkey, sign_count, state).await?;
+
+    let user = users::service::get_user_by_id(&passkey.user_id.to_string(), state).await?;
 
-    match users::service::get_user_by_id_apple(&claims.sub, state).await {
-        Ok(user) => signin_user(&user, state).await,
+    signin_user(&user, state).await
+}
+
+pub async fn signin_provider(
+    provider: &str,
+    dto: &ProviderCredentialDto,
+    state: &AppState,
+) -> Result<SigninResponse, ApiError> {
+    let identity_provider = state.authman.identity_provider(provider)?;
+    let verified_identity = identity_provider.verify(dto, &state.http_client).await?;
+    let provider = identity_provider.name();
+
+    match identities::service::get_user_identity(provider, &verified_identity.subject, state).await
+    {
+        Ok(user_identity) => {
+            let user =
+                users::service::get_user_by_id(&user_identity.user_id.to_string(), state).await?;
+            signin_first_factor(&user, state).await
+        }
         Err(e) => match e.code {
-            StatusCode::NOT_FOUND => signup_apple(&claims.email, &claims.sub, state).await,
-            _ => Err(ApiError::internal_server_error()),
+            StatusCode::NOT_FOUND => {
+                let Some(email) = &verified_identity.email else {
+                    return Err(ApiError::new(
+                        StatusCode::UNAUTHORIZED,
+                        "Provider did not share an email.",
+                    ));
+                };
+
+                let user = match users::service::get_user_by_email(email, state).await {
+                    Ok(user) => {
+                        if !verified_identity.email_verified {
+                            return Err(ApiError::new(
+                                StatusCode::UNAUTHORIZED,
+                                "Provider email is not verified.",
+                            ));
+                        }
+                        user
+                    }
+                    Err(e) => match e.code {
+                        StatusCode::NOT_FOUND => {
+                            let user = User::new(&None, email, &None);
+                            users::service::create_user(user, state).await?
+                        }
+                        _ => return Err(e),
+                    },
+                };
+
+                let user_identity =
+                    UserIdentity::new(&user.id.to_string(), provider, &verified_identity);
+                identities::service::create_user_identity(&user_identity, state).await?;
+
+                signin_first_factor(&user, state).await
+            }
+            _ => Err(e),
         },
     }
 }
 
 async fn signin_user(user: &User, state: &AppState) -> Result<AccessInfo, ApiError> {
-    let claims = AccessTokenClaims::new(&user.id.to_string());
-    let Ok(access_token) = claims.to_jwt(&state.envy.jwt_secret, None) else {
+    let Ok(device) = devices::service::create_device(user, state).await else {
         return Err(ApiError::internal_server_error());
     };
 
-    let Ok(device) = devices::service::create_device(user, state).await else {
+    let claims = AccessTokenClaims::new(
+        &user.id.to_string(),
+        Some(&device.id.to_string()),
+        TokenType::ACCESS,
+        &Scope::ALL,
+    );
+    let Ok(access_token) = claims.to_jwt(state.authman.keyring(), None) else {
         return Err(ApiError::internal_server_error());
     };
 
@@ -136,8 +578,13 @@ pub async fn refresh(dto: &RefreshAccessInfoDto, state: &AppState) -> Result<Acc
 
     match refresh_device_result {
         Ok(device) => {
-            let claims = AccessTokenClaims::new(&device.user_id.to_string());
-            let Ok(access_token) = claims.to_jwt(&state.envy.jwt_secret, None) else {
+            let claims = AccessTokenClaims::new(
+                &device.user_id.to_string(),
+                Some(&device.id.to_string()),
+                TokenType::ACCESS,
+                &Scope::ALL,
+            );
+            let Ok(access_token) = claims.to_jwt(state.authman.keyring(), None) else {
                 return Err(ApiError::internal_server_error());
             };
 
@@ -148,10 +595,41 @@ pub async fn refresh(dto: &RefreshAccessInfoDto, state: &AppState) -> Result<Acc
             })
         }
         Err(e) => match e.code {
-            StatusCode::NOT_FOUND => Err(ApiError::new(
-                StatusCode::UNAUTHORIZED,
-                "Device not found or expired.",
-            )),
+            StatusCode::NOT_FOUND => {
+                let Ok(device) =
+                    devices::service::revoke_device_by_rotated_token(&dto.refresh_token, state)
+                        .await
+                else {
+                    return Err(ApiError::new(
+                        StatusCode::UNAUTHORIZED,
+                        "Device not found or expired.",
+                    ));
+                };
+
+                tracing::warn!("refresh token reuse detected for device {}", device.id);
+
+                let user_id = device.user_id.to_string();
+                let state = state.clone();
+
+                tokio::spawn(async move {
+                    let Ok(user) = users::service::get_user_by_id(&user_id, &state).await else {
+                        return;
+                    };
+                    let mail_template = refresh_token_reuse_template::new();
+                    let _ = mail::service::send(
+                        &user.email,
+                        &mail_template.0,
+                        &mail_template.1,
+                        &state.envy,
+                    )
+                    .await;
+                });
+
+                Err(ApiError::new(
+                    StatusCode::UNAUTHORIZED,
+                    "Device not found or expired.",
+                ))
+            }
             _ => Err(e),
         },
     }
@@ -180,16 +658,16 @@ pub async fn request_email_update(
         return Err(ApiError::new(StatusCode::CONFLICT, "Email already exists."));
     }
 
-    users::service::edit_user_email_pending(&claims.id, &dto.new_email, state).await?;
+    users::service::edit_user_email_pending(&claims.sub, &dto.new_email, state).await?;
 
-    let id = claims.id.clone();
+    let id = claims.sub.clone();
     let envy = state.envy.clone();
+    let keyring = state.authman.keyring().clone();
     let new_email = dto.new_email.clone();
 
     tokio::spawn(async move {
-        let temp_claims = AccessTokenClaims::new(&id);
-        let Ok(access_token) = temp_claims.to_jwt(&envy.jwt_secret, Some(PepperType::EDIT_EMAIL))
-        else {
+        let temp_claims = AccessTokenClaims::new(&id, None, TokenType::EDIT_EMAIL, &[]);
+        let Ok(access_token) = temp_claims.to_jwt(&keyring, Some(PepperType::EDIT_EMAIL)) else {
             return;
         };
         let mail_template = request_email_update_template::new(&access_token);
@@ -203,21 +681,27 @@ pub async fn process_email_update(
     claims: &AccessTokenClaims,
     state: &AppState,
 ) -> Result<(), ApiError> {
-    return users::service::approve_user_email_pending(&claims.id, state).await;
+    return users::service::approve_user_email_pending(&claims.sub, state).await;
 }
 
 pub async fn request_password_update(
     dto: &RequestPasswordUpdateDto,
     state: &AppState,
 ) -> Result<(), ApiError> {
-    let user = users::service::get_user_by_email(&dto.email, state).await?;
+    let user = match users::service::get_user_by_email(&dto.email, state).await {
+        Ok(user) => user,
+        Err(e) => match e.code {
+            StatusCode::NOT_FOUND => return Ok(()),
+            _ => return Err(e),
+        },
+    };
     let envy = state.envy.clone();
+    let keyring = state.authman.keyring().clone();
 
     tokio::spawn(async move {
-        let temp_claims = AccessTokenClaims::new(&user.id.to_string());
-        let Ok(access_token) =
-            temp_claims.to_jwt(&envy.jwt_secret, Some(PepperType::EDIT_PASSWORD))
-        else {
+        let temp_claims =
+            AccessTokenClaims::new(&user.id.to_string(), None, TokenType::EDIT_PASSWORD, &[]);
+        let Ok(access_token) = temp_claims.to_jwt(&keyring, Some(PepperType::EDIT_PASSWORD)) else {
             return;
         };
         let mail_template = request_password_update_template::new(&access_token);
@@ -232,5 +716,12 @@ pub async fn edit_password(
     claims: &AccessTokenClaims,
     state: &AppState,
 ) -> Result<(), ApiError> {
-    return users::service::edit_user_password(&claims.id, &dto.password, state).await;
+    let user = users::service::get_user_by_id(&claims.sub, state).await?;
+    state
+        .authman
+        .password_policy()
+        .check(&dto.password, &user.username, &user.email)
+        .await?;
+
+    users::service::edit_user_password(&claims.sub, &dto.password, state).await
 }
@@ -0,0 +1,11 @@
+use super::secret_token;
+
+pub static PREFIX: &str = "pq_";
+
+pub fn new() -> String {
+    [PREFIX, &secret_token::new()].concat()
+}
+
+pub fn is_api_key(value: &str) -> bool {
+    value.starts_with(PREFIX)
+}
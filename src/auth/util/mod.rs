@@ -1 +1,6 @@
+pub mod api_key;
 pub mod password;
+pub mod recovery_code;
+pub mod secret_token;
+pub mod totp;
+pub mod webauthn;
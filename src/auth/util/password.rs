@@ -1,16 +1,40 @@
+use std::sync::OnceLock;
+
 use argon2::{
     password_hash::{self, SaltString},
-    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
 };
 use tokio::task;
 
 use crate::app::models::app_error::AppError;
 
-pub async fn hash(password: String) -> Result<String, AppError> {
+static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+
+fn argon2(params: Params) -> Argon2<'static> {
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+}
+
+pub fn params(
+    memory_cost: Option<u32>,
+    time_cost: Option<u32>,
+    parallelism: Option<u32>,
+) -> Result<Params, AppError> {
+    match Params::new(
+        memory_cost.unwrap_or(Params::DEFAULT_M_COST),
+        time_cost.unwrap_or(Params::DEFAULT_T_COST),
+        parallelism.unwrap_or(Params::DEFAULT_P_COST),
+        None,
+    ) {
+        Ok(params) => Ok(params),
+        Err(_) => Err(AppError::new("password::params invalid argon2 params")),
+    }
+}
+
+pub async fn hash(password: String, params: Params) -> Result<String, AppError> {
     let task_result = task::spawn_blocking(move || {
         let salt = SaltString::generate(rand::thread_rng());
 
-        match Argon2::default().hash_password(password.as_bytes(), &salt) {
+        match argon2(params).hash_password(password.as_bytes(), &salt) {
             Ok(hash) => Ok(hash.to_string()),
             Err(_) => Err(AppError::new("password::hash failed to hash password")),
         }
@@ -23,6 +47,7 @@ pub async fn hash(password: String) -> Result<String, AppError> {
     }
 }
 
+// verifies with the params encoded in the hash, not the configured ones
 pub async fn verify(password: String, hash: String) -> Result<bool, AppError> {
     let task_result = task::spawn_blocking(move || match PasswordHash::new(&hash) {
         Ok(hash) => match Argon2::default().verify_password(password.as_bytes(), &hash) {
@@ -39,3 +64,43 @@ pub async fn verify(password: String, hash: String) -> Result<bool, AppError> {
         Err(_) => Err(AppError::new("password::verify task failed")),
     }
 }
+
+pub fn needs_rehash(hash: &str, params: &Params) -> bool {
+    let Ok(hash) = PasswordHash::new(hash) else {
+        return true;
+    };
+    if hash.algorithm != Algorithm::Argon2id.ident() || hash.version != Some(Version::V0x13.into())
+    {
+        return true;
+    }
+
+    match Params::try_from(&hash) {
+        Ok(hash_params) => {
+            hash_params.m_cost() != params.m_cost()
+                || hash_params.t_cost() != params.t_cost()
+                || hash_params.p_cost() != params.p_cost()
+        }
+        Err(_) => true,
+    }
+}
+
+// keeps sign-in timing the same whether or not the account exists
+pub async fn verify_dummy(password: String, params: Params) -> Result<bool, AppError> {
+    let task_result = task::spawn_blocking(move || {
+        DUMMY_HASH
+            .get_or_init(|| {
+                let salt = SaltString::generate(rand::thread_rng());
+                argon2(params)
+                    .hash_password(b"dummy-password", &salt)
+                    .expect("failed to hash dummy password")
+                    .to_string()
+            })
+            .to_string()
+    })
+    .await;
+    let Ok(hash) = task_result else {
+        return Err(AppError::new("password::verify_dummy task failed"));
+    };
+
+    verify(password, hash).await
+}
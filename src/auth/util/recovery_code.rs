@@ -0,0 +1,21 @@
+use rand::{distributions::Uniform, thread_rng, Rng};
+
+static ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+pub fn new() -> String {
+    let mut rng = thread_rng();
+    let range = Uniform::from(0..ALPHABET.len());
+    let chars: Vec<char> = (0..10)
+        .map(|_| ALPHABET[rng.sample(range)] as char)
+        .collect();
+
+    format!(
+        "{}-{}",
+        chars[..5].iter().collect::<String>(),
+        chars[5..].iter().collect::<String>()
+    )
+}
+
+pub fn is_recovery_code(value: &str) -> bool {
+    value.len() == 11 && value.chars().nth(5) == Some('-')
+}
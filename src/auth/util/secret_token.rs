@@ -0,0 +1,26 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use rand::{thread_rng, RngCore};
+use sha2::Sha256;
+
+pub fn new() -> String {
+    let mut bytes = [0u8; 32];
+    thread_rng().fill_bytes(&mut bytes);
+
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+// the domain keeps hashes made with the same secret apart across token kinds
+pub fn hash(value: &str, secret: &str, domain: &str) -> String {
+    URL_SAFE_NO_PAD.encode(hmac(secret, &[domain.as_bytes(), b":", value.as_bytes()]))
+}
+
+pub fn hmac(secret: &str, parts: &[&[u8]]) -> Vec<u8> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts keys of any length");
+    for part in parts {
+        mac.update(part);
+    }
+
+    mac.finalize().into_bytes().to_vec()
+}
@@ -0,0 +1,64 @@
+use rand::{thread_rng, RngCore};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use crate::app::{self, models::app_error::AppError};
+
+static STEP: u64 = 30;
+
+pub fn new_secret() -> String {
+    let mut bytes = [0u8; 20];
+    thread_rng().fill_bytes(&mut bytes);
+
+    Secret::Raw(bytes.to_vec()).to_encoded().to_string()
+}
+
+fn totp(secret: &str, account_name: &str) -> Result<TOTP, AppError> {
+    let Ok(secret) = Secret::Encoded(secret.to_string()).to_bytes() else {
+        return Err(AppError::new("totp::totp failed to decode secret"));
+    };
+
+    match TOTP::new(
+        Algorithm::SHA1,
+        6,
+        1,
+        STEP,
+        secret,
+        Some(app::config::APP_NAME.to_string()),
+        account_name.to_string(),
+    ) {
+        Ok(totp) => Ok(totp),
+        Err(_) => Err(AppError::new("totp::totp failed to create totp")),
+    }
+}
+
+pub fn otpauth_uri(secret: &str, account_name: &str) -> Result<String, AppError> {
+    Ok(totp(secret, account_name)?.get_url())
+}
+
+// returns the time step the code was generated for, so callers can
+// refuse a code that was already used
+pub fn verify(secret: &str, code: &str) -> Result<Option<i64>, AppError> {
+    let totp = totp(secret, "")?;
+    let current_time_in_secs = app::util::time::current_time_in_secs() as u64;
+
+    let mut step = None;
+    for time in [
+        current_time_in_secs - STEP,
+        current_time_in_secs,
+        current_time_in_secs + STEP,
+    ] {
+        if constant_time_eq(totp.generate(time).as_bytes(), code.as_bytes()) && step.is_none() {
+            step = Some((time / STEP) as i64);
+        }
+    }
+
+    Ok(step)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
@@ -0,0 +1,593 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ciborium::Value;
+use openssl::{
+    bn::BigNum,
+    ec::{EcGroup, EcKey},
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{PKey, Public},
+    rsa::Rsa,
+    sign::Verifier,
+};
+use rand::{thread_rng, RngCore};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::app::{self, models::app_error::AppError};
+
+pub static ALG_ES256: i64 = -7;
+pub static ALG_RS256: i64 = -257;
+
+static FLAG_USER_PRESENT: u8 = 0x01;
+static FLAG_USER_VERIFIED: u8 = 0x04;
+static FLAG_ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+
+#[derive(Debug, Deserialize)]
+struct ClientData {
+    #[serde(rename(deserialize = "type"))]
+    ceremony_type: String,
+    challenge: String,
+    origin: String,
+}
+
+struct AuthenticatorData {
+    rp_id_hash: Vec<u8>,
+    flags: u8,
+    sign_count: u32,
+    attested_credential: Option<(Vec<u8>, Value)>,
+}
+
+pub struct AttestedCredential {
+    pub credential_id: String,
+    pub public_key: String,
+    pub algorithm: i64,
+    pub sign_count: i64,
+}
+
+pub fn new_challenge() -> String {
+    let mut bytes = [0u8; 32];
+    thread_rng().fill_bytes(&mut bytes);
+
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, AppError> {
+    match URL_SAFE_NO_PAD.decode(value.trim_end_matches('=')) {
+        Ok(bytes) => Ok(bytes),
+        Err(_) => Err(AppError::new("webauthn::decode failed to decode base64url")),
+    }
+}
+
+fn verify_client_data(
+    client_data_json: &[u8],
+    ceremony_type: &str,
+    challenge: &str,
+) -> Result<(), AppError> {
+    let Ok(client_data) = serde_json::from_slice::<ClientData>(client_data_json) else {
+        return Err(AppError::new("webauthn failed to decode client data"));
+    };
+
+    if client_data.ceremony_type != ceremony_type {
+        return Err(AppError::new("webauthn client data has wrong type"));
+    }
+    if client_data.challenge.trim_end_matches('=') != challenge {
+        return Err(AppError::new("webauthn client data has wrong challenge"));
+    }
+    if client_data.origin != app::config::FRONTEND_URL {
+        return Err(AppError::new("webauthn client data has wrong origin"));
+    }
+
+    Ok(())
+}
+
+fn parse_authenticator_data(auth_data: &[u8]) -> Result<AuthenticatorData, AppError> {
+    if auth_data.len() < 37 {
+        return Err(AppError::new("webauthn authenticator data is too short"));
+    }
+
+    let flags = auth_data[32];
+    let sign_count =
+        u32::from_be_bytes([auth_data[33], auth_data[34], auth_data[35], auth_data[36]]);
+
+    let mut attested_credential = None;
+    if flags & FLAG_ATTESTED_CREDENTIAL_DATA != 0 {
+        // aaguid (16 bytes) followed by a big endian credential id length
+        if auth_data.len() < 55 {
+            return Err(AppError::new("webauthn attested credential is too short"));
+        }
+        let credential_id_len = u16::from_be_bytes([auth_data[53], auth_data[54]]) as usize;
+        if auth_data.len() < 55 + credential_id_len {
+            return Err(AppError::new("webauthn credential id is too short"));
+        }
+        let credential_id = auth_data[55..55 + credential_id_len].to_vec();
+        let Ok(cose_key) =
+            ciborium::de::from_reader::<Value, _>(&auth_data[55 + credential_id_len..])
+        else {
+            return Err(AppError::new(
+                "webauthn failed to decode credential public key",
+            ));
+        };
+
+        attested_credential = Some((credential_id, cose_key));
+    }
+
+    Ok(AuthenticatorData {
+        rp_id_hash: auth_data[..32].to_vec(),
+        flags,
+        sign_count,
+        attested_credential,
+    })
+}
+
+fn verify_authenticator_data(auth_data: &AuthenticatorData) -> Result<(), AppError> {
+    let rp_id_hash = Sha256::digest(app::config::WEBAUTHN_RP_ID.as_bytes());
+    if auth_data.rp_id_hash != rp_id_hash.as_slice() {
+        return Err(AppError::new("webauthn authenticator data has wrong rp id"));
+    }
+    if auth_data.flags & FLAG_USER_PRESENT == 0 || auth_data.flags & FLAG_USER_VERIFIED == 0 {
+        return Err(AppError::new("webauthn user was not verified"));
+    }
+
+    Ok(())
+}
+
+fn cose_value(cose_key: &Value, label: i64) -> Option<&Value> {
+    let Value::Map(entries) = cose_key else {
+        return None;
+    };
+
+    entries.iter().find_map(|(key, value)| match key {
+        Value::Integer(key) if i128::from(*key) == label as i128 => Some(value),
+        _ => None,
+    })
+}
+
+fn cose_bytes(cose_key: &Value, label: i64) -> Result<Vec<u8>, AppError> {
+    match cose_value(cose_key, label) {
+        Some(Value::Bytes(bytes)) => Ok(bytes.to_vec()),
+        _ => Err(AppError::new("webauthn credential public key is malformed")),
+    }
+}
+
+fn public_key_from_cose(cose_key: &Value) -> Result<(PKey<Public>, i64), AppError> {
+    let algorithm = match cose_value(cose_key, 3) {
+        Some(Value::Integer(algorithm)) => i128::from(*algorithm) as i64,
+        _ => return Err(AppError::new("webauthn credential public key has no alg")),
+    };
+
+    let pkey = if algorithm == ALG_ES256 {
+        let x = cose_bytes(cose_key, -2)?;
+        let y = cose_bytes(cose_key, -3)?;
+
+        EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)
+            .and_then(|group| {
+                EcKey::from_public_key_affine_coordinates(
+                    &group,
+                    &*BigNum::from_slice(&x)?,
+                    &*BigNum::from_slice(&y)?,
+                )
+            })
+            .and_then(PKey::from_ec_key)
+    } else if algorithm == ALG_RS256 {
+        let n = cose_bytes(cose_key, -1)?;
+        let e = cose_bytes(cose_key, -2)?;
+
+        BigNum::from_slice(&n)
+            .and_then(|n| Rsa::from_public_components(n, BigNum::from_slice(&e)?))
+            .and_then(PKey::from_rsa)
+    } else {
+        return Err(AppError::new(
+            "webauthn credential algorithm is unsupported",
+        ));
+    };
+
+    match pkey {
+        Ok(pkey) => Ok((pkey, algorithm)),
+        Err(_) => Err(AppError::new(
+            "webauthn failed to build credential public key",
+        )),
+    }
+}
+
+// attestation statements are not checked since registration options ask
+// for "none" attestation
+pub fn verify_registration(
+    client_data_json: &str,
+    attestation_object: &str,
+    challenge: &str,
+) -> Result<AttestedCredential, AppError> {
+    verify_client_data(&decode(client_data_json)?, "webauthn.create", challenge)?;
+
+    let Ok(attestation_object) =
+        ciborium::de::from_reader::<Value, _>(decode(attestation_object)?.as_slice())
+    else {
+        return Err(AppError::new(
+            "webauthn failed to decode attestation object",
+        ));
+    };
+    let Value::Map(entries) = attestation_object else {
+        return Err(AppError::new("webauthn attestation object is malformed"));
+    };
+    let Some(Value::Bytes(auth_data)) = entries.iter().find_map(|(key, value)| match key {
+        Value::Text(key) if key == "authData" => Some(value),
+        _ => None,
+    }) else {
+        return Err(AppError::new("webauthn attestation object has no authData"));
+    };
+
+    let auth_data = parse_authenticator_data(auth_data)?;
+    verify_authenticator_data(&auth_data)?;
+
+    let Some((credential_id, cose_key)) = &auth_data.attested_credential else {
+        return Err(AppError::new("webauthn attestation has no credential"));
+    };
+    let (public_key, algorithm) = public_key_from_cose(cose_key)?;
+    let Ok(public_key_der) = public_key.public_key_to_der() else {
+        return Err(AppError::new(
+            "webauthn failed to encode credential public key",
+        ));
+    };
+
+    Ok(AttestedCredential {
+        credential_id: URL_SAFE_NO_PAD.encode(credential_id),
+        public_key: URL_SAFE_NO_PAD.encode(public_key_der),
+        algorithm,
+        sign_count: auth_data.sign_count as i64,
+    })
+}
+
+// returns the authenticator's new signature counter
+pub fn verify_assertion(
+    client_data_json: &str,
+    authenticator_data: &str,
+    signature: &str,
+    challenge: &str,
+    public_key: &str,
+    sign_count: i64,
+) -> Result<i64, AppError> {
+    let client_data_json = decode(client_data_json)?;
+    verify_client_data(&client_data_json, "webauthn.get", challenge)?;
+
+    let auth_data_bytes = decode(authenticator_data)?;
+    let auth_data = parse_authenticator_data(&auth_data_bytes)?;
+    verify_authenticator_data(&auth_data)?;
+
+    let Ok(public_key) = PKey::public_key_from_der(&decode(public_key)?) else {
+        return Err(AppError::new("webauthn failed to decode stored public key"));
+    };
+    let client_data_hash = Sha256::digest(&client_data_json);
+    let signature = decode(signature)?;
+
+    let verified = Verifier::new(MessageDigest::sha256(), &public_key).and_then(|mut verifier| {
+        verifier.update(&auth_data_bytes)?;
+        verifier.update(&client_data_hash)?;
+        verifier.verify(&signature)
+    });
+
+    if !matches!(verified, Ok(true)) {
+        return Err(AppError::new("webauthn signature is invalid"));
+    }
+
+    // authenticators that do not implement a counter always report zero
+    let new_sign_count = auth_data.sign_count as i64;
+    if (new_sign_count != 0 || sign_count != 0) && new_sign_count <= sign_count {
+        return Err(AppError::new("webauthn sign count went backwards"));
+    }
+
+    Ok(new_sign_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{bn::BigNumContext, sign::Signer};
+    use serde_json::json;
+
+    use super::*;
+
+    static CHALLENGE: &str = "c2lnbi1pbi1jaGFsbGVuZ2U";
+    static CREDENTIAL_ID: &[u8] = b"credential-id";
+
+    struct Authenticator {
+        key: PKey<openssl::pkey::Private>,
+    }
+
+    impl Authenticator {
+        fn new() -> Self {
+            let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+            let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+
+            Self { key }
+        }
+
+        fn public_key(&self) -> String {
+            URL_SAFE_NO_PAD.encode(self.key.public_key_to_der().unwrap())
+        }
+
+        fn cose_key(&self) -> Vec<u8> {
+            let ec_key = self.key.ec_key().unwrap();
+            let mut x = BigNum::new().unwrap();
+            let mut y = BigNum::new().unwrap();
+            ec_key
+                .public_key()
+                .affine_coordinates(
+                    ec_key.group(),
+                    &mut x,
+                    &mut y,
+                    &mut BigNumContext::new().unwrap(),
+                )
+                .unwrap();
+
+            let cose_key = Value::Map(vec![
+                (Value::Integer(1.into()), Value::Integer(2.into())),
+                (Value::Integer(3.into()), Value::Integer(ALG_ES256.into())),
+                (Value::Integer((-1).into()), Value::Integer(1.into())),
+                (
+                    Value::Integer((-2).into()),
+                    Value::Bytes(x.to_vec_padded(32).unwrap()),
+                ),
+                (
+                    Value::Integer((-3).into()),
+                    Value::Bytes(y.to_vec_padded(32).unwrap()),
+                ),
+            ]);
+            let mut bytes = Vec::new();
+            ciborium::ser::into_writer(&cose_key, &mut bytes).unwrap();
+
+            bytes
+        }
+
+        fn sign(&self, auth_data: &[u8], client_data_json: &[u8]) -> String {
+            let mut signer = Signer::new(MessageDigest::sha256(), &self.key).unwrap();
+            signer.update(auth_data).unwrap();
+            signer.update(&Sha256::digest(client_data_json)).unwrap();
+
+            URL_SAFE_NO_PAD.encode(signer.sign_to_vec().unwrap())
+        }
+    }
+
+    fn client_data(ceremony_type: &str, challenge: &str, origin: &str) -> Vec<u8> {
+        serde_json::to_vec(&json!({
+            "type": ceremony_type,
+            "challenge": challenge,
+            "origin": origin,
+        }))
+        .unwrap()
+    }
+
+    fn auth_data(rp_id: &str, flags: u8, sign_count: u32) -> Vec<u8> {
+        let mut bytes = Sha256::digest(rp_id.as_bytes()).to_vec();
+        bytes.push(flags);
+        bytes.extend_from_slice(&sign_count.to_be_bytes());
+
+        bytes
+    }
+
+    fn attestation_object(authenticator: &Authenticator, auth_data: &[u8]) -> String {
+        let mut auth_data = auth_data.to_vec();
+        auth_data[32] |= FLAG_ATTESTED_CREDENTIAL_DATA;
+        auth_data.extend_from_slice(&[0u8; 16]);
+        auth_data.extend_from_slice(&(CREDENTIAL_ID.len() as u16).to_be_bytes());
+        auth_data.extend_from_slice(CREDENTIAL_ID);
+        auth_data.extend_from_slice(&authenticator.cose_key());
+
+        let attestation_object = Value::Map(vec![
+            (
+                Value::Text("fmt".to_string()),
+                Value::Text("none".to_string()),
+            ),
+            (Value::Text("attStmt".to_string()), Value::Map(vec![])),
+            (Value::Text("authData".to_string()), Value::Bytes(auth_data)),
+        ]);
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&attestation_object, &mut bytes).unwrap();
+
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    fn get_assertion(
+        authenticator: &Authenticator,
+        client_data_json: &[u8],
+        auth_data: &[u8],
+        sign_count: i64,
+    ) -> Result<i64, AppError> {
+        verify_assertion(
+            &URL_SAFE_NO_PAD.encode(client_data_json),
+            &URL_SAFE_NO_PAD.encode(auth_data),
+            &authenticator.sign(auth_data, client_data_json),
+            CHALLENGE,
+            &authenticator.public_key(),
+            sign_count,
+        )
+    }
+
+    fn error_message<T>(result: Result<T, AppError>) -> String {
+        match result {
+            Ok(_) => panic!("expected an error"),
+            Err(e) => e.message,
+        }
+    }
+
+    fn valid_client_data() -> Vec<u8> {
+        client_data("webauthn.get", CHALLENGE, app::config::FRONTEND_URL)
+    }
+
+    fn valid_auth_data(sign_count: u32) -> Vec<u8> {
+        auth_data(
+            app::config::WEBAUTHN_RP_ID,
+            FLAG_USER_PRESENT | FLAG_USER_VERIFIED,
+            sign_count,
+        )
+    }
+
+    #[test]
+    fn registration_returns_the_attested_credential() {
+        let authenticator = Authenticator::new();
+        let client_data_json = client_data("webauthn.create", CHALLENGE, app::config::FRONTEND_URL);
+
+        let credential = verify_registration(
+            &URL_SAFE_NO_PAD.encode(client_data_json),
+            &attestation_object(&authenticator, &valid_auth_data(1)),
+            CHALLENGE,
+        )
+        .unwrap();
+
+        assert_eq!(
+            credential.credential_id,
+            URL_SAFE_NO_PAD.encode(CREDENTIAL_ID)
+        );
+        assert_eq!(credential.public_key, authenticator.public_key());
+        assert_eq!(credential.algorithm, ALG_ES256);
+        assert_eq!(credential.sign_count, 1);
+    }
+
+    #[test]
+    fn registration_rejects_other_rp_id() {
+        let authenticator = Authenticator::new();
+        let client_data_json = client_data("webauthn.create", CHALLENGE, app::config::FRONTEND_URL);
+        let auth_data = auth_data(
+            "evil.example.com",
+            FLAG_USER_PRESENT | FLAG_USER_VERIFIED,
+            1,
+        );
+
+        let registration = verify_registration(
+            &URL_SAFE_NO_PAD.encode(client_data_json),
+            &attestation_object(&authenticator, &auth_data),
+            CHALLENGE,
+        );
+
+        assert_eq!(
+            error_message(registration),
+            "webauthn authenticator data has wrong rp id"
+        );
+    }
+
+    #[test]
+    fn assertion_returns_the_new_sign_count() {
+        let authenticator = Authenticator::new();
+
+        let sign_count =
+            get_assertion(&authenticator, &valid_client_data(), &valid_auth_data(8), 7);
+
+        assert_eq!(sign_count.unwrap(), 8);
+    }
+
+    #[test]
+    fn assertion_rejects_other_rp_id() {
+        let authenticator = Authenticator::new();
+        let auth_data = auth_data(
+            "evil.example.com",
+            FLAG_USER_PRESENT | FLAG_USER_VERIFIED,
+            1,
+        );
+
+        let assertion = get_assertion(&authenticator, &valid_client_data(), &auth_data, 0);
+
+        assert_eq!(
+            error_message(assertion),
+            "webauthn authenticator data has wrong rp id"
+        );
+    }
+
+    #[test]
+    fn assertion_rejects_missing_user_presence_or_verification() {
+        let authenticator = Authenticator::new();
+
+        for flags in [FLAG_USER_PRESENT, FLAG_USER_VERIFIED, 0] {
+            let auth_data = auth_data(app::config::WEBAUTHN_RP_ID, flags, 1);
+            let assertion = get_assertion(&authenticator, &valid_client_data(), &auth_data, 0);
+
+            assert_eq!(error_message(assertion), "webauthn user was not verified");
+        }
+    }
+
+    #[test]
+    fn assertion_rejects_other_challenge() {
+        let authenticator = Authenticator::new();
+        let client_data_json = client_data(
+            "webauthn.get",
+            "b3RoZXItY2hhbGxlbmdl",
+            app::config::FRONTEND_URL,
+        );
+
+        let assertion = get_assertion(&authenticator, &client_data_json, &valid_auth_data(1), 0);
+
+        assert_eq!(
+            error_message(assertion),
+            "webauthn client data has wrong challenge"
+        );
+    }
+
+    #[test]
+    fn assertion_rejects_other_origin() {
+        let authenticator = Authenticator::new();
+        let client_data_json = client_data("webauthn.get", CHALLENGE, "https://evil.example.com");
+
+        let assertion = get_assertion(&authenticator, &client_data_json, &valid_auth_data(1), 0);
+
+        assert_eq!(
+            error_message(assertion),
+            "webauthn client data has wrong origin"
+        );
+    }
+
+    #[test]
+    fn assertion_rejects_registration_client_data() {
+        let authenticator = Authenticator::new();
+        let client_data_json = client_data("webauthn.create", CHALLENGE, app::config::FRONTEND_URL);
+
+        let assertion = get_assertion(&authenticator, &client_data_json, &valid_auth_data(1), 0);
+
+        assert_eq!(
+            error_message(assertion),
+            "webauthn client data has wrong type"
+        );
+    }
+
+    #[test]
+    fn assertion_rejects_signature_from_other_key() {
+        let authenticator = Authenticator::new();
+        let client_data_json = valid_client_data();
+        let auth_data = valid_auth_data(1);
+
+        let verified = verify_assertion(
+            &URL_SAFE_NO_PAD.encode(&client_data_json),
+            &URL_SAFE_NO_PAD.encode(&auth_data),
+            &Authenticator::new().sign(&auth_data, &client_data_json),
+            CHALLENGE,
+            &authenticator.public_key(),
+            0,
+        );
+
+        assert_eq!(error_message(verified), "webauthn signature is invalid");
+    }
+
+    #[test]
+    fn assertion_rejects_sign_count_that_did_not_increase() {
+        let authenticator = Authenticator::new();
+
+        for sign_count in [7, 3, 0] {
+            let assertion = get_assertion(
+                &authenticator,
+                &valid_client_data(),
+                &valid_auth_data(sign_count),
+                7,
+            );
+
+            assert_eq!(
+                error_message(assertion),
+                "webauthn sign count went backwards"
+            );
+        }
+    }
+
+    #[test]
+    fn assertion_accepts_authenticator_without_counter() {
+        let authenticator = Authenticator::new();
+
+        let sign_count =
+            get_assertion(&authenticator, &valid_client_data(), &valid_auth_data(0), 0);
+
+        assert_eq!(sign_count.unwrap(), 0);
+    }
+}
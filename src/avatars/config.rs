@@ -0,0 +1,5 @@
+pub static AVATAR_SIZES: [u32; 3] = [64, 256, 512];
+pub static AVATAR_MAX_BYTES: usize = 5242880;
+pub static AVATAR_MAX_DIMENSION: u32 = 4096;
+pub static AVATAR_MAX_ALLOC: u64 = 67108864;
+pub static AVATAR_JPEG_QUALITY: u8 = 85;
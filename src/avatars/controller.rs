@@ -0,0 +1,53 @@
+use axum::{
+    extract::{Multipart, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    app::models::{api_error::ApiError, app_state::AppState},
+    auth::{enums::scope::Scope, models::access_token_claims::ExtractClaims},
+    users::models::user::User,
+};
+
+use super::service;
+
+pub async fn upload_avatar(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+    mut multipart: Multipart,
+) -> Result<Json<User>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+
+    let mut bytes = None;
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return Err(ApiError::new(e.status(), &e.body_text())),
+        };
+        if field.name() != Some("avatar") {
+            continue;
+        }
+        match field.bytes().await {
+            Ok(data) => bytes = Some(data.to_vec()),
+            Err(e) => return Err(ApiError::new(e.status(), &e.body_text())),
+        }
+    }
+    let Some(bytes) = bytes else {
+        return Err(ApiError::new(StatusCode::BAD_REQUEST, "Missing avatar."));
+    };
+
+    match service::upload_avatar(bytes, &claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn delete_avatar(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<(), ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    service::delete_avatar(&claims, &state).await
+}
@@ -0,0 +1,62 @@
+use tokio::task;
+
+use crate::{
+    app::{
+        models::{api_error::ApiError, app_state::AppState},
+        util::time,
+    },
+    auth::models::access_token_claims::AccessTokenClaims,
+    users::{self, models::user::User},
+};
+
+use super::{config::AVATAR_SIZES, util::avatar_image};
+
+fn avatar_key(user_id: &str, size: u32) -> String {
+    format!("avatars/{}/{}.jpg", user_id, size)
+}
+
+// avatar_url points at the largest size, the other sizes sit beside it
+pub async fn upload_avatar(
+    bytes: Vec<u8>,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<User, ApiError> {
+    let Ok(process_result) = task::spawn_blocking(move || avatar_image::process(&bytes)).await
+    else {
+        return Err(ApiError::internal_server_error());
+    };
+    let images = process_result?;
+
+    for (size, image) in images {
+        let key = avatar_key(&claims.sub, size);
+        if state.storage.put(&key, image, "image/jpeg").await.is_err() {
+            return Err(ApiError::internal_server_error());
+        }
+    }
+
+    let largest_size = AVATAR_SIZES[AVATAR_SIZES.len() - 1];
+    let avatar_url = format!(
+        "{}?v={}",
+        state.storage.url(&avatar_key(&claims.sub, largest_size)),
+        time::current_time_in_millis()
+    );
+
+    users::service::edit_user_avatar_url(&claims.sub, Some(&avatar_url), state).await
+}
+
+pub async fn delete_avatar(claims: &AccessTokenClaims, state: &AppState) -> Result<(), ApiError> {
+    users::service::edit_user_avatar_url(&claims.sub, None, state).await?;
+
+    for size in AVATAR_SIZES {
+        if state
+            .storage
+            .delete(&avatar_key(&claims.sub, size))
+            .await
+            .is_err()
+        {
+            return Err(ApiError::internal_server_error());
+        }
+    }
+
+    Ok(())
+}
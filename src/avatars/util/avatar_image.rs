@@ -0,0 +1,79 @@
+use std::io::Cursor;
+
+use axum::http::StatusCode;
+use image::{
+    codecs::jpeg::JpegEncoder, imageops::FilterType, metadata::Orientation, DynamicImage,
+    ImageDecoder, ImageFormat, ImageReader, Limits, Rgb, RgbImage,
+};
+
+use crate::{
+    app::models::api_error::ApiError,
+    avatars::config::{AVATAR_JPEG_QUALITY, AVATAR_MAX_ALLOC, AVATAR_MAX_DIMENSION, AVATAR_SIZES},
+};
+
+// decodes and re-encodes every size, which drops exif and any other metadata
+pub fn process(bytes: &[u8]) -> Result<Vec<(u32, Vec<u8>)>, ApiError> {
+    let Ok(mut reader) = ImageReader::new(Cursor::new(bytes)).with_guessed_format() else {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "Failed to read avatar.",
+        ));
+    };
+    match reader.format() {
+        Some(ImageFormat::Png) | Some(ImageFormat::Jpeg) | Some(ImageFormat::WebP) => {}
+        _ => {
+            return Err(ApiError::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Avatar must be a PNG, JPEG or WebP image.",
+            ))
+        }
+    }
+
+    // a few kilobytes of compressed pixels can claim gigabytes once decoded,
+    // so the decoder allocation is capped on top of the dimensions
+    let mut limits = Limits::default();
+    limits.max_alloc = Some(AVATAR_MAX_ALLOC);
+    limits.max_image_width = Some(AVATAR_MAX_DIMENSION);
+    limits.max_image_height = Some(AVATAR_MAX_DIMENSION);
+    reader.limits(limits);
+
+    let Ok(mut decoder) = reader.into_decoder() else {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "Failed to read avatar.",
+        ));
+    };
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let Ok(mut image) = DynamicImage::from_decoder(decoder) else {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "Failed to read avatar.",
+        ));
+    };
+    image.apply_orientation(orientation);
+
+    let mut images = Vec::new();
+    for size in AVATAR_SIZES {
+        let resized = image.resize_to_fill(size, size, FilterType::Lanczos3);
+        let mut buffer = Vec::new();
+        let encoder = JpegEncoder::new_with_quality(&mut buffer, AVATAR_JPEG_QUALITY);
+        if let Err(e) = flatten(&resized).write_with_encoder(encoder) {
+            tracing::error!(%e);
+            return Err(ApiError::internal_server_error());
+        }
+        images.push((size, buffer));
+    }
+
+    Ok(images)
+}
+
+// jpeg has no alpha channel, so transparent pixels are blended onto white
+fn flatten(image: &DynamicImage) -> RgbImage {
+    let rgba = image.to_rgba8();
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let alpha = a as u32;
+        let blend = |channel: u8| ((channel as u32 * alpha + 255 * (255 - alpha)) / 255) as u8;
+        Rgb([blend(r), blend(g), blend(b)])
+    })
+}
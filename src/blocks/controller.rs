@@ -0,0 +1,43 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+use crate::{
+    app::models::{api_error::ApiError, app_state::AppState},
+    auth::{enums::scope::Scope, models::access_token_claims::ExtractClaims},
+};
+
+use super::{models::block::Block, service};
+
+pub async fn create_block(
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<Json<Block>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    match service::create_block(&user_id, &claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_blocks(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<Json<Vec<Block>>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    match service::get_blocks(&claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn delete_block(
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<(), ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    service::delete_block(&user_id, &claims, &state).await
+}
@@ -0,0 +1,23 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+use crate::app;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Block {
+    pub user_id: sqlx::types::Uuid,
+    pub blocked_user_id: sqlx::types::Uuid,
+    pub created_at: i64,
+}
+
+impl Block {
+    pub fn new(user_id: &str, blocked_user_id: &str) -> Self {
+        Self {
+            user_id: sqlx::types::Uuid::from_str(user_id).unwrap(),
+            blocked_user_id: sqlx::types::Uuid::from_str(blocked_user_id).unwrap(),
+            created_at: app::util::time::current_time_in_millis(),
+        }
+    }
+}
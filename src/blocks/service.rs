@@ -0,0 +1,153 @@
+use std::str::FromStr;
+
+use axum::http::StatusCode;
+use sqlx::{types::Uuid, Postgres};
+
+use crate::{
+    app::models::{api_error::ApiError, app_state::AppState},
+    auth::models::access_token_claims::AccessTokenClaims,
+    users,
+};
+
+use super::models::block::Block;
+
+pub async fn create_block(
+    blocked_user_id: &str,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<Block, ApiError> {
+    if blocked_user_id == claims.sub {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "You cannot block yourself.",
+        ));
+    }
+    let blocked_user = users::service::get_user_by_id(blocked_user_id, state).await?;
+
+    let block = Block::new(&claims.sub, &blocked_user.id.to_string());
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO blocks (user_id, blocked_user_id, created_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, blocked_user_id) DO NOTHING
+        ",
+    )
+    .bind(block.user_id)
+    .bind(block.blocked_user_id)
+    .bind(block.created_at)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(block),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to block user.",
+            ))
+        }
+    }
+}
+
+pub async fn get_blocks(
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<Vec<Block>, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, Block>(
+        "
+        SELECT * FROM blocks
+        WHERE user_id = $1
+        ORDER BY created_at DESC
+        ",
+    )
+    .bind(Uuid::from_str(&claims.sub).unwrap_or_default())
+    .fetch_all(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(blocks) => Ok(blocks),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get blocks.",
+            ))
+        }
+    }
+}
+
+pub async fn delete_block(
+    blocked_user_id: &str,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let Ok(blocked_user_id) = Uuid::from_str(blocked_user_id) else {
+        return Err(ApiError::new(StatusCode::NOT_FOUND, "Block not found."));
+    };
+
+    let sqlx_result = sqlx::query(
+        "
+        DELETE FROM blocks
+        WHERE user_id = $1 AND blocked_user_id = $2
+        ",
+    )
+    .bind(Uuid::from_str(&claims.sub).unwrap_or_default())
+    .bind(blocked_user_id)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(ApiError::new(StatusCode::NOT_FOUND, "Block not found.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to unblock user.",
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+
+    use crate::app::test_util;
+
+    use super::*;
+
+    #[sqlx::test(migrations = false)]
+    async fn blocks_lists_and_unblocks_user(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let claims = test_util::access_claims(&test_util::insert_user("someone", &state).await);
+        let blocked_user_id = test_util::insert_user("someoneelse", &state)
+            .await
+            .to_string();
+
+        create_block(&blocked_user_id, &claims, &state)
+            .await
+            .unwrap();
+        let blocks = get_blocks(&claims, &state).await.unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].blocked_user_id.to_string(), blocked_user_id);
+
+        delete_block(&blocked_user_id, &claims, &state)
+            .await
+            .unwrap();
+        assert!(get_blocks(&claims, &state).await.unwrap().is_empty());
+
+        let e = delete_block(&blocked_user_id, &claims, &state)
+            .await
+            .unwrap_err();
+        assert_eq!(e.code, StatusCode::NOT_FOUND);
+        let e = delete_block("not-a-uuid", &claims, &state)
+            .await
+            .unwrap_err();
+        assert_eq!(e.code, StatusCode::NOT_FOUND);
+    }
+}
@@ -6,7 +6,7 @@ use validator::Validate;
 
 use crate::{
     app::models::{api_error::ApiError, app_state::AppState},
-    auth::models::access_token_claims::ExtractClaims,
+    auth::{enums::scope::Scope, models::access_token_claims::ExtractClaims},
 };
 
 use super::{
@@ -20,6 +20,7 @@ pub async fn get_devices(
     ExtractClaims(claims): ExtractClaims,
     Query(dto): Query<GetDevicesFilterDto>,
 ) -> Result<Json<Vec<Device>>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
     dto.validate()?;
     match service::get_devices(&dto, Some(&claims), &state).await {
         Ok(data) => Ok(Json(data)),
@@ -33,6 +34,7 @@ pub async fn edit_device(
     ExtractClaims(claims): ExtractClaims,
     Json(dto): Json<EditDeviceDto>,
 ) -> Result<Json<Device>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
     dto.validate()?;
     match service::edit_device(&id, &dto, &claims, &state).await {
         Ok(data) => Ok(Json(data)),
@@ -2,15 +2,22 @@ use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 use uuid::Uuid;
 
-use crate::{app, users::models::user::User};
+use crate::{
+    app,
+    auth::{enums::secret_token_domain::SecretTokenDomain, util::secret_token},
+    users::models::user::User,
+};
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Device {
     pub id: sqlx::types::Uuid,
     pub user_id: sqlx::types::Uuid,
     #[serde(skip_serializing)]
+    #[sqlx(skip)]
     pub refresh_token: String,
     #[serde(skip_serializing)]
+    pub refresh_token_hash: Option<String>,
+    #[serde(skip_serializing)]
     pub messaging_token: Option<String>,
     pub refreshed_at: i64,
     pub updated_at: i64,
@@ -18,13 +25,20 @@ pub struct Device {
 }
 
 impl Device {
-    pub fn new(user: &User) -> Self {
+    pub fn new(user: &User, refresh_token_secret: &str) -> Self {
         let current_time = app::util::time::current_time_in_millis();
+        let refresh_token = secret_token::new();
+        let refresh_token_hash = secret_token::hash(
+            &refresh_token,
+            refresh_token_secret,
+            SecretTokenDomain::REFRESH_TOKEN,
+        );
 
         Self {
             id: Uuid::new_v4(),
             user_id: user.id,
-            refresh_token: Uuid::new_v4().to_string(),
+            refresh_token,
+            refresh_token_hash: Some(refresh_token_hash),
             messaging_token: None,
             refreshed_at: current_time,
             updated_at: current_time,
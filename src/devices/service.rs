@@ -1,6 +1,7 @@
+use std::str::FromStr;
+
 use axum::http::StatusCode;
-use sqlx::Postgres;
-use uuid::Uuid;
+use sqlx::{types::Uuid, Postgres};
 
 use crate::{
     app::{
@@ -8,7 +9,12 @@ use crate::{
         models::{api_error::ApiError, app_state::AppState},
         util::time,
     },
-    auth::models::access_token_claims::AccessTokenClaims,
+    auth::{
+        config::{REFRESH_TOKEN_ABSOLUTE_EXP, REFRESH_TOKEN_IDLE_EXP},
+        enums::secret_token_domain::SecretTokenDomain,
+        models::access_token_claims::AccessTokenClaims,
+        util::secret_token,
+    },
     users::models::user::User,
 };
 
@@ -18,12 +24,12 @@ use super::{
 };
 
 pub async fn create_device(user: &User, state: &AppState) -> Result<Device, ApiError> {
-    let device = Device::new(user);
+    let device = Device::new(user, &state.envy.refresh_token_secret);
 
     let sqlx_result = sqlx::query(
         "
         INSERT INTO devices (
-            id, user_id, refresh_token, messaging_token,
+            id, user_id, refresh_token_hash, messaging_token,
             refreshed_at, updated_at, created_at
         )
         VALUES ($1, $2, $3, $4, $5, $6, $7)
@@ -31,7 +37,7 @@ pub async fn create_device(user: &User, state: &AppState) -> Result<Device, ApiE
     )
     .bind(&device.id)
     .bind(&device.user_id)
-    .bind(&device.refresh_token)
+    .bind(&device.refresh_token_hash)
     .bind(&device.messaging_token)
     .bind(&device.refreshed_at)
     .bind(&device.updated_at)
@@ -73,6 +79,10 @@ pub async fn get_devices(
         index += 1;
         query.push_str(&format!(" AND id = ${}", index));
     }
+    if dto.user_id.is_some() {
+        index += 1;
+        query.push_str(&format!(" AND user_id = ${}", index));
+    }
 
     // SQL SORT
     if let Some(sort) = &dto.sort {
@@ -113,10 +123,13 @@ pub async fn get_devices(
     let mut sqlx = sqlx::query_as::<Postgres, Device>(&query);
 
     if let Some(claims) = claims {
-        sqlx = sqlx.bind(&claims.id);
+        sqlx = sqlx.bind(Uuid::from_str(&claims.sub).unwrap_or_default());
     }
     if let Some(id) = &dto.id {
-        sqlx = sqlx.bind(id)
+        sqlx = sqlx.bind(Uuid::from_str(id).unwrap_or_default())
+    }
+    if let Some(user_id) = &dto.user_id {
+        sqlx = sqlx.bind(Uuid::from_str(user_id).unwrap_or_default())
     }
 
     let sqlx_result = sqlx.fetch_all(&state.pool).await;
@@ -134,19 +147,142 @@ pub async fn get_devices(
 }
 
 pub async fn refresh_device(refresh_token: &str, state: &AppState) -> Result<Device, ApiError> {
-    let new_refresh_token = Uuid::new_v4().to_string();
+    let refresh_token_hash = secret_token::hash(
+        refresh_token,
+        &state.envy.refresh_token_secret,
+        SecretTokenDomain::REFRESH_TOKEN,
+    );
+    let new_refresh_token = secret_token::new();
+    let new_refresh_token_hash = secret_token::hash(
+        &new_refresh_token,
+        &state.envy.refresh_token_secret,
+        SecretTokenDomain::REFRESH_TOKEN,
+    );
     let current_time = time::current_time_in_millis();
+    let idle_cutoff = current_time - (REFRESH_TOKEN_IDLE_EXP * 1000) as i64;
+    let absolute_cutoff = current_time - (REFRESH_TOKEN_ABSOLUTE_EXP * 1000) as i64;
+
+    let Ok(mut tx) = state.pool.begin().await else {
+        return Err(ApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to refresh device.",
+        ));
+    };
 
     let sqlx_result = sqlx::query_as::<Postgres, Device>(
         "
-        UPDATE devices SET refresh_token = $1, refreshed_at = $2, updated_at = $3
-        WHERE refresh_token = $4 RETURNING *
+        UPDATE devices
+        SET refresh_token = NULL, refresh_token_hash = $1, refreshed_at = $2, updated_at = $3
+        WHERE (refresh_token_hash = $4 OR (refresh_token_hash IS NULL AND refresh_token = $5))
+        AND refreshed_at > $6 AND created_at > $7
+        RETURNING *
+        ",
+    )
+    .bind(&new_refresh_token_hash)
+    .bind(current_time)
+    .bind(current_time)
+    .bind(&refresh_token_hash)
+    .bind(refresh_token)
+    .bind(idle_cutoff)
+    .bind(absolute_cutoff)
+    .fetch_optional(&mut *tx)
+    .await;
+
+    let device = match sqlx_result {
+        Ok(Some(mut device)) => {
+            device.refresh_token = new_refresh_token;
+            device
+        }
+        Ok(None) => {
+            // the token may still belong to a device that outlived its lifetime
+            let sqlx_result = sqlx::query(
+                "
+                DELETE FROM devices
+                WHERE refresh_token_hash = $1 OR (refresh_token_hash IS NULL AND refresh_token = $2)
+                ",
+            )
+            .bind(&refresh_token_hash)
+            .bind(refresh_token)
+            .execute(&mut *tx)
+            .await;
+
+            if let Err(e) = sqlx_result {
+                tracing::error!(%e);
+                return Err(ApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to refresh device.",
+                ));
+            }
+            if let Err(e) = tx.commit().await {
+                tracing::error!(%e);
+                return Err(ApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to refresh device.",
+                ));
+            }
+
+            return Err(ApiError::new(StatusCode::NOT_FOUND, "Device not found."));
+        }
+        Err(e) => {
+            tracing::error!(%e);
+            return Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to refresh device.",
+            ));
+        }
+    };
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO devices_rotated_tokens (refresh_token_hash, device_id, rotated_at)
+        VALUES ($1, $2, $3)
         ",
     )
-    .bind(&new_refresh_token)
-    .bind(&current_time)
-    .bind(&current_time)
-    .bind(&refresh_token)
+    .bind(&refresh_token_hash)
+    .bind(device.id)
+    .bind(current_time)
+    .execute(&mut *tx)
+    .await;
+
+    if let Err(e) = sqlx_result {
+        tracing::error!(%e);
+        return Err(ApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to refresh device.",
+        ));
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(device),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to refresh device.",
+            ))
+        }
+    }
+}
+
+pub async fn revoke_device_by_rotated_token(
+    refresh_token: &str,
+    state: &AppState,
+) -> Result<Device, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, Device>(
+        "
+        DELETE FROM devices
+        WHERE id = (
+            SELECT device_id FROM devices_rotated_tokens
+            WHERE refresh_token_hash = $1
+        )
+        RETURNING *
+        ",
+    )
+    .bind(secret_token::hash(
+        refresh_token,
+        &state.envy.refresh_token_secret,
+        SecretTokenDomain::REFRESH_TOKEN,
+    ))
     .fetch_optional(&state.pool)
     .await;
 
@@ -159,7 +295,7 @@ pub async fn refresh_device(refresh_token: &str, state: &AppState) -> Result<Dev
             tracing::error!(%e);
             Err(ApiError::new(
                 StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to refresh device.",
+                "Failed to revoke device.",
             ))
         }
     }
@@ -196,8 +332,8 @@ pub async fn edit_device(
         sqlx = sqlx.bind(messaging_token);
     }
     sqlx = sqlx.bind(time::current_time_in_millis());
-    sqlx = sqlx.bind(id);
-    sqlx = sqlx.bind(&claims.id);
+    sqlx = sqlx.bind(Uuid::from_str(id).unwrap_or_default());
+    sqlx = sqlx.bind(Uuid::from_str(&claims.sub).unwrap_or_default());
 
     let sqlx_result = sqlx.fetch_optional(&state.pool).await;
 
@@ -227,8 +363,8 @@ pub async fn delete_device(
         WHERE id = $1 AND user_id = $2
         ",
     )
-    .bind(id)
-    .bind(&claims.id)
+    .bind(Uuid::from_str(id).unwrap_or_default())
+    .bind(Uuid::from_str(&claims.sub).unwrap_or_default())
     .execute(&state.pool)
     .await;
 
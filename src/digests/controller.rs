@@ -0,0 +1,120 @@
+use axum::extract::{Path, State};
+
+use crate::{
+    app::models::{api_error::ApiError, app_state::AppState},
+    auth::models::access_token_claims::ExtractClaimsPepperUnsubscribeDigest,
+};
+
+use super::service;
+
+pub async fn unsubscribe_digest(
+    State(state): State<AppState>,
+    ExtractClaimsPepperUnsubscribeDigest(claims): ExtractClaimsPepperUnsubscribeDigest,
+) -> Result<(), ApiError> {
+    service::unsubscribe_digest(&claims, &state).await
+}
+
+pub async fn unsubscribe_digest_one_click(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<(), ApiError> {
+    service::unsubscribe_digest_one_click(&token, &state).await
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        extract::{Path, State},
+        response::IntoResponse,
+    };
+    use sqlx::{types::Uuid, PgPool};
+
+    use crate::{
+        app::test_util,
+        auth::{
+            config::UNSUBSCRIBE_DIGEST_EXP,
+            enums::{pepper_type::PepperType, token_type::TokenType},
+            models::access_token_claims::AccessTokenClaims,
+        },
+        AppState,
+    };
+
+    use super::unsubscribe_digest_one_click;
+
+    async fn subscribed_user(state: &AppState) -> Uuid {
+        let user_id = Uuid::new_v4();
+        sqlx::query(
+            "
+            INSERT INTO users (id, username, username_key, email, email_key, displayname, updated_at, created_at)
+            VALUES ($1, 'someone', 'someone', 'someone@example.com', 'someone@example.com', 'someone', 0, 0)
+            ",
+        )
+        .bind(user_id)
+        .execute(&state.pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "
+            INSERT INTO user_settings (user_id, reminder_lead_time, timezone, default_memo_priority, default_memo_visibility, locale, week_start, notification_channels, digest_enabled, updated_at, created_at)
+            VALUES ($1, 0, 'UTC', 0, 0, 'en', 0, '{push}', true, 0, 0)
+            ",
+        )
+        .bind(user_id)
+        .execute(&state.pool)
+        .await
+        .unwrap();
+
+        user_id
+    }
+
+    async fn digest_enabled(user_id: &Uuid, state: &AppState) -> bool {
+        sqlx::query_scalar("SELECT digest_enabled FROM user_settings WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&state.pool)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn one_click_unsubscribe_disables_digest(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let user_id = subscribed_user(&state).await;
+
+        let mut claims = AccessTokenClaims::new(
+            &user_id.to_string(),
+            None,
+            TokenType::UNSUBSCRIBE_DIGEST,
+            &[],
+        );
+        claims.exp = claims.iat + UNSUBSCRIBE_DIGEST_EXP;
+        let token = claims
+            .to_jwt(
+                state.authman.keyring(),
+                Some(PepperType::UNSUBSCRIBE_DIGEST),
+            )
+            .unwrap();
+
+        let response = unsubscribe_digest_one_click(State(state.clone()), Path(token))
+            .await
+            .into_response();
+
+        assert_eq!(response.status().as_u16(), 200);
+        assert!(!digest_enabled(&user_id, &state).await);
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn one_click_unsubscribe_rejects_access_tokens(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let user_id = subscribed_user(&state).await;
+
+        let claims = AccessTokenClaims::new(&user_id.to_string(), None, TokenType::ACCESS, &[]);
+        let token = claims.to_jwt(state.authman.keyring(), None).unwrap();
+
+        let response = unsubscribe_digest_one_click(State(state.clone()), Path(token))
+            .await
+            .into_response();
+
+        assert_eq!(response.status().as_u16(), 401);
+        assert!(digest_enabled(&user_id, &state).await);
+    }
+}
@@ -0,0 +1,144 @@
+use std::str::FromStr;
+
+use chrono::{NaiveDate, TimeZone};
+use chrono_tz::Tz;
+
+use crate::{
+    app::{
+        self,
+        models::{api_error::ApiError, app_state::AppState},
+        util::time,
+    },
+    auth::{
+        config::UNSUBSCRIBE_DIGEST_EXP,
+        enums::{pepper_type::PepperType, token_type::TokenType},
+        models::access_token_claims::AccessTokenClaims,
+    },
+    mail::{self, templates::digest_template},
+    memos::{self, models::memo::Memo},
+    settings::{self, models::user_settings::UserSettings},
+    users,
+};
+
+static DIGEST_BATCH_LIMIT: i64 = 100;
+static DIGEST_MEMOS_LIMIT: i64 = 50;
+static DIGEST_LEASE: u64 = 300;
+
+pub async fn send_due_digests(state: &AppState) -> Result<(), ApiError> {
+    let due_user_settings =
+        settings::service::get_due_digest_user_settings(DIGEST_BATCH_LIMIT, state).await?;
+
+    for user_settings in due_user_settings {
+        if let Err(e) = send_digest(&user_settings, state).await {
+            tracing::error!(
+                "failed to send digest to {}: {}",
+                user_settings.user_id,
+                e.message
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn unsubscribe_digest(
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    settings::service::disable_digest(&claims.sub, state).await
+}
+
+// the token comes in the path since mail providers cannot send it as a bearer header
+pub async fn unsubscribe_digest_one_click(token: &str, state: &AppState) -> Result<(), ApiError> {
+    let claims = AccessTokenClaims::from_typed_jwt(
+        token,
+        state.authman.keyring(),
+        Some(PepperType::UNSUBSCRIBE_DIGEST),
+        TokenType::UNSUBSCRIBE_DIGEST,
+    )?;
+
+    unsubscribe_digest(&claims, state).await
+}
+
+async fn send_digest(user_settings: &UserSettings, state: &AppState) -> Result<(), ApiError> {
+    let current_time = time::current_time_in_millis();
+    let timezone = Tz::from_str(&user_settings.timezone).unwrap_or(Tz::UTC);
+    let Some(local_time) = timezone.timestamp_millis_opt(current_time).single() else {
+        return Err(ApiError::internal_server_error());
+    };
+    let today = local_time.date_naive();
+    let (Some(start_of_day), Some(end_of_day)) = (
+        start_of_day(&timezone, today),
+        today
+            .succ_opt()
+            .and_then(|tomorrow| start_of_day(&timezone, tomorrow)),
+    ) else {
+        return Err(ApiError::internal_server_error());
+    };
+
+    let leased_until = current_time + (DIGEST_LEASE * 1000) as i64;
+    if !settings::service::claim_digest(&user_settings.user_id, start_of_day, leased_until, state)
+        .await?
+    {
+        return Ok(());
+    }
+
+    let user_id = user_settings.user_id.to_string();
+
+    let mut memos = memos::service::get_pending_memos_before(
+        &user_settings.user_id,
+        end_of_day,
+        DIGEST_MEMOS_LIMIT,
+        state,
+    )
+    .await?;
+    memos.reverse();
+    let (overdue, upcoming): (Vec<Memo>, Vec<Memo>) = memos
+        .into_iter()
+        .partition(|memo| memo.trigger_at < current_time);
+    if upcoming.is_empty() && overdue.is_empty() {
+        return settings::service::mark_digest_sent(&user_settings.user_id, state).await;
+    }
+
+    let user = users::service::get_user_by_id(&user_id, state).await?;
+
+    let mut claims = AccessTokenClaims::new(&user_id, None, TokenType::UNSUBSCRIBE_DIGEST, &[]);
+    claims.exp = claims.iat + UNSUBSCRIBE_DIGEST_EXP;
+    let Ok(unsubscribe_token) = claims.to_jwt(
+        state.authman.keyring(),
+        Some(PepperType::UNSUBSCRIBE_DIGEST),
+    ) else {
+        return Err(ApiError::internal_server_error());
+    };
+    let unsubscribe_url = format!(
+        "{}/digests/unsubscribe/{}",
+        app::config::FRONTEND_URL,
+        unsubscribe_token
+    );
+    let one_click_unsubscribe_url = format!(
+        "{}/v1/digests/unsubscribe/{}",
+        state.envy.api_url.trim_end_matches('/'),
+        unsubscribe_token
+    );
+
+    let mail_template = digest_template::new(&upcoming, &overdue, &timezone, &unsubscribe_url);
+    mail::service::send_alternative(
+        &user.email,
+        &mail_template.0,
+        &mail_template.1,
+        &mail_template.2,
+        Some(&one_click_unsubscribe_url),
+        &state.envy,
+    )
+    .await?;
+
+    settings::service::mark_digest_sent(&user_settings.user_id, state).await
+}
+
+fn start_of_day(timezone: &Tz, date: NaiveDate) -> Option<i64> {
+    let midnight = date.and_hms_opt(0, 0, 0)?;
+    timezone
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|local_time| local_time.timestamp_millis())
+}
@@ -0,0 +1,49 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use validator::Validate;
+
+use crate::{
+    app::models::{api_error::ApiError, app_state::AppState},
+    auth::{
+        dtos::provider_credential_dto::ProviderCredentialDto, enums::scope::Scope,
+        models::access_token_claims::ExtractClaims,
+    },
+};
+
+use super::{models::user_identity::UserIdentity, service};
+
+pub async fn get_identities(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<Json<Vec<UserIdentity>>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    match service::get_user_identities(&claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn link_identity(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    ExtractClaims(claims): ExtractClaims,
+    Json(dto): Json<ProviderCredentialDto>,
+) -> Result<Json<UserIdentity>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    dto.validate()?;
+    match service::link_identity(&provider, &dto, &claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn unlink_identity(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<(), ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    service::unlink_identity(&provider, &claims, &state).await
+}
@@ -0,0 +1,36 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::{app, auth::providers::models::verified_identity::VerifiedIdentity};
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct UserIdentity {
+    pub id: sqlx::types::Uuid,
+    pub user_id: sqlx::types::Uuid,
+    pub provider: String,
+    #[serde(skip_serializing)]
+    pub subject: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub updated_at: i64,
+    pub created_at: i64,
+}
+
+impl UserIdentity {
+    pub fn new(user_id: &str, provider: &str, verified_identity: &VerifiedIdentity) -> Self {
+        let current_time = app::util::time::current_time_in_millis();
+
+        Self {
+            id: Uuid::new_v4(),
+            user_id: Uuid::from_str(user_id).unwrap(),
+            provider: provider.to_string(),
+            subject: verified_identity.subject.to_string(),
+            email: verified_identity.email.clone(),
+            updated_at: current_time,
+            created_at: current_time,
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use std::str::FromStr;
+
+use axum::http::StatusCode;
+use sqlx::{types::Uuid, Postgres};
+
+use crate::{
+    app::{
+        self,
+        models::{api_error::ApiError, app_state::AppState},
+    },
+    auth::{
+        dtos::provider_credential_dto::ProviderCredentialDto,
+        models::access_token_claims::AccessTokenClaims,
+    },
+    passkeys, users,
+};
+
+use super::models::user_identity::UserIdentity;
+
+pub async fn create_user_identity(
+    user_identity: &UserIdentity,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO user_identities (
+            id, user_id, provider, subject, email, updated_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ",
+    )
+    .bind(user_identity.id)
+    .bind(user_identity.user_id)
+    .bind(&user_identity.provider)
+    .bind(&user_identity.subject)
+    .bind(&user_identity.email)
+    .bind(user_identity.updated_at)
+    .bind(user_identity.created_at)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            let Some(db_err) = e.as_database_error() else {
+                tracing::error!(%e);
+                return Err(ApiError::internal_server_error());
+            };
+            let Some(code) = app::util::sqlx::extract_db_err_code(db_err) else {
+                tracing::error!(%e);
+                return Err(ApiError::internal_server_error());
+            };
+
+            match code.as_str() {
+                app::util::sqlx::SqlStateCodes::UNIQUE_VIOLATION => Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    "Identity already linked.",
+                )),
+                _ => {
+                    tracing::error!(%e);
+                    Err(ApiError::new(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Failed to link identity.",
+                    ))
+                }
+            }
+        }
+    }
+}
+
+pub async fn get_user_identity(
+    provider: &str,
+    subject: &str,
+    state: &AppState,
+) -> Result<UserIdentity, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, UserIdentity>(
+        "
+        SELECT * FROM user_identities
+        WHERE provider = $1 AND subject = $2
+        ",
+    )
+    .bind(provider)
+    .bind(subject)
+    .fetch_optional(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(user_identity) => match user_identity {
+            Some(user_identity) => Ok(user_identity),
+            None => Err(ApiError::new(StatusCode::NOT_FOUND, "Identity not found.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get identity.",
+            ))
+        }
+    }
+}
+
+pub async fn get_user_identities(
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<Vec<UserIdentity>, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, UserIdentity>(
+        "
+        SELECT * FROM user_identities
+        WHERE user_id = $1
+        ORDER BY created_at DESC
+        ",
+    )
+    .bind(Uuid::from_str(&claims.sub).unwrap_or_default())
+    .fetch_all(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(user_identities) => Ok(user_identities),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get identities.",
+            ))
+        }
+    }
+}
+
+pub async fn link_identity(
+    provider: &str,
+    dto: &ProviderCredentialDto,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<UserIdentity, ApiError> {
+    let identity_provider = state.authman.identity_provider(provider)?;
+    let verified_identity = identity_provider.verify(dto, &state.http_client).await?;
+
+    let user_identity =
+        UserIdentity::new(&claims.sub, identity_provider.name(), &verified_identity);
+    create_user_identity(&user_identity, state).await?;
+
+    Ok(user_identity)
+}
+
+pub async fn unlink_identity(
+    provider: &str,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let user = users::service::get_user_by_id(&claims.sub, state).await?;
+    let user_identities = get_user_identities(claims, state).await?;
+    let passkeys = passkeys::service::get_passkeys(claims, state).await?;
+
+    let has_other_identity = user_identities
+        .iter()
+        .any(|user_identity| user_identity.provider != provider);
+    if user.password.is_none() && !has_other_identity && passkeys.is_empty() {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "Cannot unlink the only sign-in method.",
+        ));
+    }
+
+    let sqlx_result = sqlx::query(
+        "
+        DELETE FROM user_identities
+        WHERE provider = $1 AND user_id = $2
+        ",
+    )
+    .bind(provider)
+    .bind(Uuid::from_str(&claims.sub).unwrap_or_default())
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(ApiError::new(StatusCode::NOT_FOUND, "Identity not found.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to unlink identity.",
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+
+    use crate::{app::test_util, auth::providers::models::verified_identity::VerifiedIdentity};
+
+    use super::*;
+
+    async fn link(provider: &str, claims: &AccessTokenClaims, state: &AppState) {
+        let verified_identity = VerifiedIdentity {
+            subject: format!("{}-subject", provider),
+            email: None,
+            email_verified: false,
+        };
+        let user_identity = UserIdentity::new(&claims.sub, provider, &verified_identity);
+        create_user_identity(&user_identity, state).await.unwrap();
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn links_lists_and_unlinks_identities(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let claims = test_util::access_claims(&test_util::insert_user("someone", &state).await);
+
+        link("google", &claims, &state).await;
+        assert_eq!(get_user_identities(&claims, &state).await.unwrap().len(), 1);
+        assert_eq!(
+            get_user_identity("google", "google-subject", &state)
+                .await
+                .unwrap()
+                .user_id
+                .to_string(),
+            claims.sub
+        );
+
+        let e = unlink_identity("google", &claims, &state)
+            .await
+            .unwrap_err();
+        assert_eq!(e.code, StatusCode::BAD_REQUEST);
+
+        link("github", &claims, &state).await;
+        unlink_identity("google", &claims, &state).await.unwrap();
+        let user_identities = get_user_identities(&claims, &state).await.unwrap();
+        assert_eq!(user_identities.len(), 1);
+        assert_eq!(user_identities[0].provider, "github");
+
+        let e = unlink_identity("apple", &claims, &state).await.unwrap_err();
+        assert_eq!(e.code, StatusCode::NOT_FOUND);
+    }
+}
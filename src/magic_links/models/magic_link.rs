@@ -0,0 +1,41 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    app,
+    auth::{
+        config::MAGIC_LINK_EXP, enums::secret_token_domain::SecretTokenDomain, util::secret_token,
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct MagicLink {
+    pub id: sqlx::types::Uuid,
+    pub user_id: sqlx::types::Uuid,
+    #[serde(skip_serializing)]
+    #[sqlx(skip)]
+    pub token: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub expires_at: i64,
+    pub created_at: i64,
+}
+
+impl MagicLink {
+    pub fn new(user_id: &str, secret: &str) -> Self {
+        let current_time = app::util::time::current_time_in_millis();
+        let token = secret_token::new();
+
+        Self {
+            id: Uuid::new_v4(),
+            user_id: Uuid::from_str(user_id).unwrap(),
+            token_hash: secret_token::hash(&token, secret, SecretTokenDomain::MAGIC_LINK),
+            token,
+            expires_at: current_time + (MAGIC_LINK_EXP * 1000) as i64,
+            created_at: current_time,
+        }
+    }
+}
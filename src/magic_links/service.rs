@@ -0,0 +1,115 @@
+use axum::http::StatusCode;
+use sqlx::Postgres;
+
+use crate::{
+    app::{
+        models::{api_error::ApiError, app_state::AppState},
+        util::time,
+    },
+    auth::{
+        config::{MAGIC_LINK_EXP, MAGIC_LINK_MAX_REQUESTS},
+        enums::secret_token_domain::SecretTokenDomain,
+        util::secret_token,
+    },
+};
+
+use super::models::magic_link::MagicLink;
+
+pub async fn create_magic_link(user_id: &str, state: &AppState) -> Result<MagicLink, ApiError> {
+    let current_time = time::current_time_in_millis();
+    let magic_link = MagicLink::new(user_id, &state.envy.refresh_token_secret);
+
+    let _ = sqlx::query("DELETE FROM magic_links WHERE expires_at <= $1")
+        .bind(current_time)
+        .execute(&state.pool)
+        .await;
+
+    let sqlx_result = sqlx::query_scalar::<Postgres, i64>(
+        "
+        SELECT COUNT(*) FROM magic_links
+        WHERE user_id = $1 AND created_at > $2
+        ",
+    )
+    .bind(magic_link.user_id)
+    .bind(current_time - (MAGIC_LINK_EXP * 1000) as i64)
+    .fetch_one(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(count) => {
+            if count >= MAGIC_LINK_MAX_REQUESTS {
+                return Err(ApiError::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "Too many sign-in links requested.",
+                ));
+            }
+        }
+        Err(e) => {
+            tracing::error!(%e);
+            return Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to create sign-in link.",
+            ));
+        }
+    }
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO magic_links (id, user_id, token_hash, expires_at, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+        ",
+    )
+    .bind(magic_link.id)
+    .bind(magic_link.user_id)
+    .bind(&magic_link.token_hash)
+    .bind(magic_link.expires_at)
+    .bind(magic_link.created_at)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(magic_link),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to create sign-in link.",
+            ))
+        }
+    }
+}
+
+pub async fn consume_magic_link(token: &str, state: &AppState) -> Result<MagicLink, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, MagicLink>(
+        "
+        DELETE FROM magic_links
+        WHERE token_hash = $1 AND expires_at > $2
+        RETURNING *
+        ",
+    )
+    .bind(secret_token::hash(
+        token,
+        &state.envy.refresh_token_secret,
+        SecretTokenDomain::MAGIC_LINK,
+    ))
+    .bind(time::current_time_in_millis())
+    .fetch_optional(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(magic_link) => match magic_link {
+            Some(magic_link) => Ok(magic_link),
+            None => Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "Invalid or expired sign-in link.",
+            )),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get sign-in link.",
+            ))
+        }
+    }
+}
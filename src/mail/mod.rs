@@ -1,2 +1,4 @@
+pub mod models;
 pub mod service;
 pub mod templates;
+pub mod util;
@@ -0,0 +1,21 @@
+use std::error::Error;
+
+use lettre::message::header::{Header, HeaderName, HeaderValue};
+
+// rfc 8058 one-click unsubscribe, mail providers POST this body to the List-Unsubscribe url
+#[derive(Debug, Clone)]
+pub struct ListUnsubscribePost;
+
+impl Header for ListUnsubscribePost {
+    fn name() -> HeaderName {
+        HeaderName::new_from_ascii_str("List-Unsubscribe-Post")
+    }
+
+    fn parse(_: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Ok(Self)
+    }
+
+    fn display(&self) -> HeaderValue {
+        HeaderValue::new(Self::name(), "List-Unsubscribe=One-Click".to_string())
+    }
+}
@@ -0,0 +1,2 @@
+pub mod list_unsubscribe;
+pub mod list_unsubscribe_post;
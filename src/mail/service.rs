@@ -1,13 +1,63 @@
 use axum::http::StatusCode;
 use lettre::{
-    message::{header::ContentType, Mailbox},
+    message::{header::ContentType, Mailbox, MultiPart},
     transport::smtp::authentication::Credentials,
-    AsyncSmtpTransport, AsyncTransport, Tokio1Executor,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
 };
 
 use crate::app::{envy::Envy, models::api_error::ApiError};
 
+use super::models::{
+    list_unsubscribe::ListUnsubscribe, list_unsubscribe_post::ListUnsubscribePost,
+};
+
 pub async fn send(to: &str, subject: &str, body: &str, envy: &Envy) -> Result<(), ApiError> {
+    let (mailbox, from) = mailboxes(to, envy)?;
+
+    let Ok(mail) = lettre::Message::builder()
+        .to(mailbox)
+        .from(from)
+        .subject(subject)
+        .header(ContentType::TEXT_HTML)
+        .body(String::from(body))
+    else {
+        return Err(ApiError::internal_server_error());
+    };
+
+    deliver(mail, envy).await
+}
+
+pub async fn send_alternative(
+    to: &str,
+    subject: &str,
+    html: &str,
+    text: &str,
+    unsubscribe_url: Option<&str>,
+    envy: &Envy,
+) -> Result<(), ApiError> {
+    let (mailbox, from) = mailboxes(to, envy)?;
+
+    let mut builder = lettre::Message::builder()
+        .to(mailbox)
+        .from(from)
+        .subject(subject);
+    if let Some(unsubscribe_url) = unsubscribe_url {
+        builder = builder
+            .header(ListUnsubscribe(unsubscribe_url.to_string()))
+            .header(ListUnsubscribePost);
+    }
+
+    let Ok(mail) = builder.multipart(MultiPart::alternative_plain_html(
+        String::from(text),
+        String::from(html),
+    )) else {
+        return Err(ApiError::internal_server_error());
+    };
+
+    deliver(mail, envy).await
+}
+
+fn mailboxes(to: &str, envy: &Envy) -> Result<(Mailbox, Mailbox), ApiError> {
     let Ok(mailbox) = to.parse::<Mailbox>() else {
         return Err(ApiError::new(
             StatusCode::BAD_REQUEST,
@@ -21,16 +71,10 @@ pub async fn send(to: &str, subject: &str, body: &str, envy: &Envy) -> Result<()
         ));
     };
 
-    let Ok(mail) = lettre::Message::builder()
-        .to(mailbox)
-        .from(from)
-        .subject(subject)
-        .header(ContentType::TEXT_HTML)
-        .body(String::from(body))
-    else {
-        return Err(ApiError::internal_server_error());
-    };
+    Ok((mailbox, from))
+}
 
+async fn deliver(mail: Message, envy: &Envy) -> Result<(), ApiError> {
     let credentials = Credentials::new(envy.mail_user.to_string(), envy.mail_pass.to_string());
     let mailer = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&envy.mail_host)
         .unwrap()
@@ -0,0 +1,23 @@
+use crate::app;
+
+pub fn new() -> (String, String) {
+    let url = format!("{}/auth/password", app::config::FRONTEND_URL);
+
+    (
+        format!("You already have a {} account", app::config::APP_NAME),
+        format!(
+            "
+            <p>Hello there!</p>
+            <p>Someone tried to create a {} account with this email, but you already have one.</p>
+            <p>If you forgot your password, you can reset it here:</p>
+            <a href={}>{}</a>
+            <p>If you did not request this, ignore this email.</p>
+            <p>Your friends at {}</p>
+            ",
+            app::config::APP_NAME,
+            url,
+            url,
+            app::config::APP_NAME
+        ),
+    )
+}
@@ -0,0 +1,74 @@
+use chrono::TimeZone;
+use chrono_tz::Tz;
+
+use crate::{app, mail::util::html, memos::models::memo::Memo};
+
+pub fn new(
+    upcoming: &[Memo],
+    overdue: &[Memo],
+    timezone: &Tz,
+    unsubscribe_url: &str,
+) -> (String, String, String) {
+    let format_time =
+        |memo: &Memo, format: &str| match timezone.timestamp_millis_opt(memo.trigger_at).single() {
+            Some(local_time) => local_time.format(format).to_string(),
+            None => String::new(),
+        };
+
+    let mut html_sections = String::new();
+    let mut text_sections = String::new();
+
+    if !upcoming.is_empty() {
+        html_sections.push_str("<h3>Today</h3><ul>");
+        text_sections.push_str("Today\n");
+        for memo in upcoming {
+            let time = format_time(memo, "%H:%M");
+            html_sections.push_str(&format!(
+                "<li>{} &middot; {}</li>",
+                time,
+                html::escape(&memo.title)
+            ));
+            text_sections.push_str(&format!("- {} {}\n", time, memo.title));
+        }
+        html_sections.push_str("</ul>");
+        text_sections.push('\n');
+    }
+
+    if !overdue.is_empty() {
+        html_sections.push_str("<h3>Overdue</h3><ul>");
+        text_sections.push_str("Overdue\n");
+        for memo in overdue {
+            let date = format_time(memo, "%b %-d");
+            html_sections.push_str(&format!(
+                "<li>{} &middot; {}</li>",
+                date,
+                html::escape(&memo.title)
+            ));
+            text_sections.push_str(&format!("- {} {}\n", date, memo.title));
+        }
+        html_sections.push_str("</ul>");
+        text_sections.push('\n');
+    }
+
+    (
+        format!("Your {} daily digest", app::config::APP_NAME),
+        format!(
+            "
+            <p>Good morning!</p>
+            <p>Here is what is on your plate.</p>
+            {}
+            <p>Your friends at {}</p>
+            <p><a href={}>Unsubscribe from daily digests</a></p>
+            ",
+            html_sections,
+            app::config::APP_NAME,
+            unsubscribe_url
+        ),
+        format!(
+            "Good morning!\n\nHere is what is on your plate.\n\n{}Your friends at {}\n\nUnsubscribe from daily digests: {}\n",
+            text_sections,
+            app::config::APP_NAME,
+            unsubscribe_url
+        ),
+    )
+}
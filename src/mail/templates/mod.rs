@@ -1,2 +1,9 @@
+pub mod account_exists_template;
+pub mod digest_template;
+pub mod magic_link_template;
+pub mod refresh_token_reuse_template;
+pub mod reminder_template;
 pub mod request_email_update_template;
 pub mod request_password_update_template;
+pub mod signin_failures_template;
+pub mod welcome_template;
@@ -0,0 +1,23 @@
+use crate::app;
+
+pub fn new() -> (String, String) {
+    let url = format!("{}/auth/password", app::config::FRONTEND_URL);
+
+    (
+        format!("{} suspicious sign-in", app::config::APP_NAME),
+        format!(
+            "
+            <p>Hello there!</p>
+            <p>A session on your {} account was used in a way that suggests it was copied to another device.</p>
+            <p>To protect your account, we signed that device out.</p>
+            <p>If this was not you, we recommend changing your password:</p>
+            <a href={}>{}</a>
+            <p>Your friends at {}</p>
+            ",
+            app::config::APP_NAME,
+            url,
+            url,
+            app::config::APP_NAME
+        ),
+    )
+}
@@ -0,0 +1,24 @@
+use crate::{app, mail::util::html};
+
+pub fn new(title: &str, body: &str) -> (String, String) {
+    let url = format!("{}/memos", app::config::FRONTEND_URL);
+
+    (
+        format!("Reminder: {}", title),
+        format!(
+            "
+            <p>Hello there!</p>
+            <p>This is your reminder for:</p>
+            <p><strong>{}</strong></p>
+            <p>{}</p>
+            <a href={}>{}</a>
+            <p>Your friends at {}</p>
+            ",
+            html::escape(title),
+            html::escape(body),
+            url,
+            url,
+            app::config::APP_NAME
+        ),
+    )
+}
@@ -0,0 +1,23 @@
+use crate::app;
+
+pub fn new() -> (String, String) {
+    let url = format!("{}/auth/password", app::config::FRONTEND_URL);
+
+    (
+        format!("{} failed sign-in attempts", app::config::APP_NAME),
+        format!(
+            "
+            <p>Hello there!</p>
+            <p>We noticed several failed attempts to sign in to your {} account.</p>
+            <p>We temporarily slowed down sign-ins to keep your account safe.</p>
+            <p>If this was not you, we recommend changing your password:</p>
+            <a href={}>{}</a>
+            <p>Your friends at {}</p>
+            ",
+            app::config::APP_NAME,
+            url,
+            url,
+            app::config::APP_NAME
+        ),
+    )
+}
@@ -0,0 +1,24 @@
+use crate::app;
+
+pub fn new(token: &str) -> (String, String) {
+    let url = format!("{}/auth/signin/{}", app::config::FRONTEND_URL, token);
+
+    (
+        format!("Welcome to {}", app::config::APP_NAME),
+        format!(
+            "
+            <p>Hello there!</p>
+            <p>Thank you for creating a {} account.</p>
+            <p>You can use the following link to sign in:</p>
+            <a href={}>{}</a>
+            <p>This link will expire in 15 minutes and can only be used once.</p>
+            <p>If you did not request this, ignore this email.</p>
+            <p>Your friends at {}</p>
+            ",
+            app::config::APP_NAME,
+            url,
+            url,
+            app::config::APP_NAME
+        ),
+    )
+}
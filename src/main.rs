@@ -1,33 +1,67 @@
-use std::{env, sync::Arc, time::Duration};
+use std::{env, net::SocketAddr, sync::Arc, time::Duration};
 
 use auth::authman::AuthMan;
 use axum::{
+    extract::DefaultBodyLimit,
     http::{
         header::{AUTHORIZATION, CONTENT_TYPE},
         HeaderValue, Method,
     },
-    routing::{get, patch, post},
+    middleware,
+    routing::{delete, get, patch, post, put},
     Router,
 };
 use sqlx::postgres::PgPoolOptions;
 use tokio::sync::RwLock;
-use tower_http::cors::CorsLayer;
+use tower_http::{cors::CorsLayer, services::ServeDir};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::{
     app::{
         fcm::{self, client::FcmClient},
         models::app_state::AppState,
+        rate_limit::{
+            self, backend::RateLimitBackend, limiter::RateLimiter, memory_backend::MemoryBackend,
+            models::rate_limit_policy::RateLimitPolicy, postgres_backend::PostgresBackend,
+        },
+        storage::{backend::StorageBackend, local_backend::LocalBackend},
     },
-    auth::apple::{self, client::AppleAuthClient},
+    auth::{
+        apple::{self, client::AppleAuthClient, provider::AppleProvider},
+        google::{self, client::GoogleAuthClient, provider::GoogleProvider},
+        jwks::{keyring::Keyring, models::key_config::KeyConfig},
+        oidc::{
+            client::OidcClient, models::provider_config::ProviderConfig, provider::OidcProvider,
+        },
+        password_policy::{
+            breached_passwords::BreachedPasswords, models::password_policy::PasswordPolicy,
+        },
+        providers::identity_provider::IdentityProvider,
+        util::password,
+    },
+    avatars::config::AVATAR_MAX_BYTES,
+    realtime::hub::RealtimeHub,
 };
 
+mod api_keys;
 mod app;
 mod auth;
+mod avatars;
+mod blocks;
 mod devices;
+mod digests;
+mod identities;
+mod magic_links;
 mod mail;
 mod memos;
+mod notifications;
+mod passkeys;
+mod realtime;
+mod recovery_codes;
+mod settings;
+mod signin_attempts;
 mod users;
+mod webhooks;
 
 #[macro_use]
 extern crate lazy_static;
@@ -66,7 +100,13 @@ async fn main() {
         )
         .allow_credentials(true)
         .allow_headers([AUTHORIZATION, CONTENT_TYPE])
-        .allow_methods([Method::POST, Method::GET, Method::PATCH, Method::DELETE]);
+        .allow_methods([
+            Method::POST,
+            Method::GET,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ]);
     let http_client = reqwest::Client::new();
 
     let apple_config = apple::models::client_config::ClientConfig {
@@ -76,6 +116,7 @@ async fn main() {
         client_id_web: format!("{}.Web", envy.apple_client_id),
         key_id: envy.apple_key_id.to_owned(),
         private_key: envy.apple_private_key.to_owned(),
+        redirect_uri: format!("{}/signin/apple", app::config::FRONTEND_URL),
     };
     let mut apple_client = AppleAuthClient::new(apple_config);
     apple_client
@@ -83,6 +124,34 @@ async fn main() {
         .await
         .expect("failed to login to apple_client");
 
+    let google_config = google::models::client_config::ClientConfig {
+        client_id_ios: envy.google_client_id_ios.to_owned(),
+        client_id_android: envy.google_client_id_android.to_owned(),
+        client_id_web: envy.google_client_id_web.to_owned(),
+    };
+    let mut google_client = GoogleAuthClient::new(google_config);
+    google_client
+        .login(&http_client)
+        .await
+        .expect("failed to login to google_client");
+
+    let mut identity_providers: Vec<Arc<dyn IdentityProvider>> = vec![
+        Arc::new(AppleProvider::new(apple_client)),
+        Arc::new(GoogleProvider::new(google_client)),
+    ];
+    if let Some(oidc_providers) = &envy.oidc_providers {
+        let provider_configs = serde_json::from_str::<Vec<ProviderConfig>>(oidc_providers)
+            .expect("failed to decode oidc_providers");
+        for provider_config in provider_configs {
+            let mut oidc_client = OidcClient::new(provider_config);
+            oidc_client
+                .login(&http_client)
+                .await
+                .expect("failed to login to oidc_client");
+            identity_providers.push(Arc::new(OidcProvider::new(oidc_client)));
+        }
+    }
+
     let fcm_config = fcm::models::client_config::ClientConfig {
         project_name: envy.fcm_project_name.to_owned(),
         client_email: envy.fcm_client_email.to_owned(),
@@ -94,9 +163,44 @@ async fn main() {
         .await
         .expect("failed to login to fcm_client");
 
+    let key_configs = match &envy.jwt_keys {
+        Some(jwt_keys) => {
+            serde_json::from_str::<Vec<KeyConfig>>(jwt_keys).expect("failed to decode jwt_keys")
+        }
+        None => Vec::new(),
+    };
+    let keyring = Keyring::new(&envy.jwt_secret, key_configs).expect("failed to load keyring");
+
+    let breached_passwords = match &envy.breached_passwords_path {
+        Some(path) => {
+            let breached_passwords =
+                BreachedPasswords::load(path).expect("failed to load breached passwords");
+            if breached_passwords.is_empty() {
+                tracing::warn!("breached passwords directory has no shards");
+            }
+            breached_passwords
+        }
+        None => BreachedPasswords::new(),
+    };
+    let password_policy = PasswordPolicy::new(
+        envy.password_min_length.unwrap_or(8),
+        envy.password_min_score.unwrap_or(3),
+        breached_passwords,
+    );
+
+    let argon2_params = password::params(
+        envy.argon2_memory_cost,
+        envy.argon2_time_cost,
+        envy.argon2_parallelism,
+    )
+    .expect("failed to load argon2 params");
+
     let authman = AuthMan::new(
-        Arc::new(RwLock::new(apple_client)),
+        identity_providers,
         Arc::new(RwLock::new(fcm_client)),
+        keyring,
+        password_policy,
+        argon2_params,
     );
 
     let pool = PgPoolOptions::new()
@@ -107,21 +211,72 @@ async fn main() {
         .expect("database connection failed");
     tracing::info!("connected to database");
 
+    let rate_limit_backend: Arc<dyn RateLimitBackend> = match envy.rate_limit_backend.as_deref() {
+        Some("postgres") => Arc::new(PostgresBackend::new(pool.clone())),
+        _ => Arc::new(MemoryBackend::new()),
+    };
+    let mut rate_limit_policies = rate_limit::config::default_policies();
+    if let Some(rate_limits) = &envy.rate_limits {
+        rate_limit_policies.extend(
+            serde_json::from_str::<Vec<RateLimitPolicy>>(rate_limits)
+                .expect("failed to decode rate_limits"),
+        );
+    }
+    let rate_limiter = RateLimiter::new(rate_limit_backend, rate_limit_policies);
+
+    let storage_path = envy.storage_path.clone().unwrap_or("uploads".to_string());
+    let storage_public_url = envy
+        .storage_public_url
+        .clone()
+        .unwrap_or("/uploads".to_string());
+    let storage: Arc<dyn StorageBackend> = match envy.storage_backend.as_deref() {
+        None | Some("local") => Arc::new(LocalBackend::new(&storage_path, &storage_public_url)),
+        Some(_) => panic!("unsupported storage_backend"),
+    };
+
     let app_state = AppState {
         envy,
         http_client,
         authman,
         pool,
+        rate_limiter,
+        storage,
+        realtime: RealtimeHub::new(),
     };
 
+    memos::polo::spawn(app_state.clone());
+    digests::polo::spawn(app_state.clone());
+    webhooks::polo::spawn(app_state.clone());
+    signin_attempts::polo::spawn(app_state.clone());
+    realtime::listener::spawn(app_state.clone());
+
     // app
     let app = Router::new()
         .route("/v1/", get(app::controller::get_root))
+        .route("/v1/sync", post(app::controller::sync))
+        .route("/.well-known/jwks.json", get(auth::controller::get_jwks))
         .route("/v1/auth/signup", post(auth::controller::signup))
         .route("/v1/auth/signin", post(auth::controller::signin))
+        .route("/v1/auth/signin/totp", post(auth::controller::signin_totp))
         .route(
-            "/v1/auth/signin/apple",
-            post(auth::controller::signin_apple),
+            "/v1/auth/signin/magic-link",
+            post(auth::controller::signin_magic_link),
+        )
+        .route(
+            "/v1/auth/signin/passkey/options",
+            post(auth::controller::request_passkey_signin),
+        )
+        .route(
+            "/v1/auth/signin/passkey",
+            post(auth::controller::signin_passkey),
+        )
+        .route(
+            "/v1/auth/signin/:provider",
+            post(auth::controller::signin_provider),
+        )
+        .route(
+            "/v1/auth/magic-link",
+            post(auth::controller::request_magic_link),
         )
         .route("/v1/auth/refresh", post(auth::controller::refresh))
         .route("/v1/auth/signout", post(auth::controller::signout))
@@ -138,14 +293,115 @@ async fn main() {
             post(auth::controller::request_password_update),
         )
         .route("/v1/auth/password", patch(auth::controller::edit_password))
+        .route("/v1/auth/totp", post(auth::controller::request_totp))
+        .route("/v1/auth/totp", patch(auth::controller::enable_totp))
+        .route("/v1/auth/totp", delete(auth::controller::disable_totp))
+        .route(
+            "/v1/auth/totp/recovery-codes",
+            post(auth::controller::regenerate_recovery_codes),
+        )
+        .route("/v1/api-keys", post(api_keys::controller::create_api_key))
+        .route("/v1/api-keys", get(api_keys::controller::get_api_keys))
+        .route(
+            "/v1/api-keys/:id",
+            delete(api_keys::controller::delete_api_key),
+        )
+        .route(
+            "/v1/passkeys/options",
+            post(passkeys::controller::request_passkey_registration),
+        )
+        .route("/v1/passkeys", post(passkeys::controller::create_passkey))
+        .route("/v1/passkeys", get(passkeys::controller::get_passkeys))
+        .route(
+            "/v1/passkeys/:id",
+            delete(passkeys::controller::delete_passkey),
+        )
+        .route(
+            "/v1/identities",
+            get(identities::controller::get_identities),
+        )
+        .route(
+            "/v1/identities/:provider",
+            post(identities::controller::link_identity),
+        )
+        .route(
+            "/v1/identities/:provider",
+            delete(identities::controller::unlink_identity),
+        )
+        .route("/v1/blocks", get(blocks::controller::get_blocks))
+        .route(
+            "/v1/blocks/:user_id",
+            post(blocks::controller::create_block),
+        )
+        .route(
+            "/v1/blocks/:user_id",
+            delete(blocks::controller::delete_block),
+        )
+        .route(
+            "/v1/digests/unsubscribe",
+            post(digests::controller::unsubscribe_digest),
+        )
+        .route(
+            "/v1/digests/unsubscribe/:token",
+            post(digests::controller::unsubscribe_digest_one_click),
+        )
         .route("/v1/devices", get(devices::controller::get_devices))
         .route("/v1/devices/:id", patch(devices::controller::edit_device))
         .route("/v1/users", get(users::controller::get_users))
         .route("/v1/users/me", get(users::controller::get_me))
+        .route("/v1/users/me", patch(users::controller::edit_me))
+        .route(
+            "/v1/users/me/avatar",
+            put(avatars::controller::upload_avatar).layer(DefaultBodyLimit::max(AVATAR_MAX_BYTES)),
+        )
+        .route(
+            "/v1/users/me/avatar",
+            delete(avatars::controller::delete_avatar),
+        )
+        .route(
+            "/v1/users/me/settings",
+            get(settings::controller::get_settings),
+        )
+        .route(
+            "/v1/users/me/settings",
+            patch(settings::controller::edit_settings),
+        )
+        .route(
+            "/v1/users/username-availability",
+            get(users::controller::get_username_availability),
+        )
         .route("/v1/memos", post(memos::controller::create_memo))
         .route("/v1/memos", get(memos::controller::get_memos))
+        .route(
+            "/v1/memos/changes",
+            get(realtime::controller::get_memo_changes),
+        )
         .route("/v1/memos/:id", get(memos::controller::get_memo))
         .route("/v1/memos/:id", patch(memos::controller::edit_memo))
+        .route("/v1/memos/:id", delete(memos::controller::delete_memo))
+        .route("/v1/webhooks", post(webhooks::controller::create_webhook))
+        .route("/v1/webhooks", get(webhooks::controller::get_webhooks))
+        .route(
+            "/v1/webhooks/:id",
+            patch(webhooks::controller::edit_webhook),
+        )
+        .route(
+            "/v1/webhooks/:id",
+            delete(webhooks::controller::delete_webhook),
+        )
+        .route(
+            "/v1/webhooks/:id/deliveries",
+            get(webhooks::controller::get_webhook_deliveries),
+        )
+        .route(
+            "/v1/webhooks/:id/test",
+            post(webhooks::controller::test_webhook),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            rate_limit::middleware::rate_limit,
+        ))
+        .nest_service("/uploads", ServeDir::new(&storage_path))
         .layer(cors)
         .with_state(app_state);
 
@@ -153,5 +409,10 @@ async fn main() {
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     tracing::info!("listening on {}", listener.local_addr().unwrap());
 
-    axum::serve(listener, app).await.unwrap()
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap()
 }
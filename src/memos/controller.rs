@@ -5,7 +5,9 @@ use axum::{
 use validator::Validate;
 
 use crate::{
-    app::models::api_error::ApiError, auth::models::access_token_claims::ExtractClaims, AppState,
+    app::models::api_error::ApiError,
+    auth::{enums::scope::Scope, models::access_token_claims::ExtractClaims},
+    AppState,
 };
 
 use super::{
@@ -21,6 +23,7 @@ pub async fn create_memo(
     ExtractClaims(claims): ExtractClaims,
     Json(dto): Json<CreateMemoDto>,
 ) -> Result<Json<Memo>, ApiError> {
+    claims.require_scope(Scope::MEMOS_WRITE)?;
     dto.validate()?;
     match service::create_memo(&dto, &claims, &state).await {
         Ok(data) => Ok(Json(data)),
@@ -33,6 +36,7 @@ pub async fn get_memos(
     ExtractClaims(claims): ExtractClaims,
     Query(dto): Query<GetMemosDto>,
 ) -> Result<Json<Vec<Memo>>, ApiError> {
+    claims.require_scope(Scope::MEMOS_READ)?;
     dto.validate()?;
     match service::get_memos(&dto, Some(&claims), &state).await {
         Ok(data) => Ok(Json(data)),
@@ -45,6 +49,7 @@ pub async fn get_memo(
     ExtractClaims(claims): ExtractClaims,
     Path(id): Path<String>,
 ) -> Result<Json<Memo>, ApiError> {
+    claims.require_scope(Scope::MEMOS_READ)?;
     match service::get_memo(&id, &claims, &state).await {
         Ok(data) => Ok(Json(data)),
         Err(e) => Err(e),
@@ -57,9 +62,19 @@ pub async fn edit_memo(
     ExtractClaims(claims): ExtractClaims,
     Json(dto): Json<EditMemoDto>,
 ) -> Result<Json<Memo>, ApiError> {
+    claims.require_scope(Scope::MEMOS_WRITE)?;
     dto.validate()?;
     match service::edit_memo(&id, &dto, &claims, &state).await {
         Ok(data) => Ok(Json(data)),
         Err(e) => Err(e),
     }
 }
+
+pub async fn delete_memo(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<(), ApiError> {
+    claims.require_scope(Scope::MEMOS_WRITE)?;
+    service::delete_memo(&id, &claims, &state).await
+}
@@ -17,8 +17,10 @@ pub struct CreateMemoDto {
         message = "description must be between 1 and 65535 characters."
     ))]
     pub description: Option<String>,
-    pub priority: i16,
-    pub visibility: i16,
+    pub priority: Option<i16>,
+    pub visibility: Option<i16>,
     pub frequency: Option<String>,
+    #[validate(custom = "crate::settings::dtos::validate_notification_channels")]
+    pub notification_channels: Option<Vec<String>>,
     pub trigger_at: i64,
 }
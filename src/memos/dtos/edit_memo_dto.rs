@@ -19,5 +19,7 @@ pub struct EditMemoDto {
     pub status: Option<String>,
     pub visibility: Option<i16>,
     pub frequency: Option<String>,
+    #[validate(custom = "crate::settings::dtos::validate_notification_channels")]
+    pub notification_channels: Option<Vec<String>>,
     pub trigger_at: Option<i64>,
 }
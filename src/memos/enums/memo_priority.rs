@@ -0,0 +1,8 @@
+#[non_exhaustive]
+pub struct MemoPriority;
+
+impl MemoPriority {
+    pub const LOW: i16 = 0;
+    pub const NORMAL: i16 = 1;
+    pub const HIGH: i16 = 2;
+}
@@ -0,0 +1,7 @@
+#[non_exhaustive]
+pub struct MemoStatus;
+
+impl MemoStatus {
+    pub const PENDING: &'static str = "pending";
+    pub const COMPLETED: &'static str = "completed";
+}
@@ -0,0 +1,2 @@
+pub mod memo_priority;
+pub mod memo_status;
@@ -1,5 +1,6 @@
 pub mod controller;
 pub mod dtos;
+pub mod enums;
 pub mod models;
 pub mod polo;
 pub mod service;
@@ -5,8 +5,10 @@ use sqlx::FromRow;
 use uuid::Uuid;
 
 use crate::{
-    app, auth::models::access_token_claims::AccessTokenClaims,
-    memos::dtos::create_memo_dto::CreateMemoDto,
+    app,
+    auth::models::access_token_claims::AccessTokenClaims,
+    memos::{dtos::create_memo_dto::CreateMemoDto, enums::memo_status::MemoStatus},
+    settings::models::user_settings::UserSettings,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -21,27 +23,33 @@ pub struct Memo {
     pub visibility: i16,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub frequency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_channels: Option<Vec<String>>,
     pub trigger_at: i64,
     pub updated_at: i64,
     pub created_at: i64,
 }
 
 impl Memo {
-    pub fn new(dto: &CreateMemoDto, claims: &AccessTokenClaims) -> Self {
+    pub fn new(dto: &CreateMemoDto, settings: &UserSettings, claims: &AccessTokenClaims) -> Self {
         let current_time = app::util::time::current_time_in_millis();
 
         Self {
             id: Uuid::from_str(&dto.id).unwrap(),
-            user_id: Uuid::from_str(&claims.id).unwrap(),
+            user_id: Uuid::from_str(&claims.sub).unwrap(),
             title: dto.title.trim().to_string(),
             description: match &dto.description {
                 Some(description) => Some(description.trim().to_string()),
                 None => None,
             },
-            priority: dto.priority.clone(),
-            status: "pending".to_string(),
-            visibility: dto.visibility,
+            priority: dto.priority.unwrap_or(settings.default_memo_priority),
+            status: MemoStatus::PENDING.to_string(),
+            visibility: dto.visibility.unwrap_or(settings.default_memo_visibility),
             frequency: dto.frequency.clone(),
+            notification_channels: dto
+                .notification_channels
+                .clone()
+                .filter(|notification_channels| !notification_channels.is_empty()),
             trigger_at: dto.trigger_at,
             updated_at: current_time,
             created_at: current_time,
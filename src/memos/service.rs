@@ -1,9 +1,15 @@
+use std::str::FromStr;
+
 use axum::http::StatusCode;
-use sqlx::Postgres;
+use sqlx::{types::Uuid, Postgres};
 
 use crate::{
     app::{self, models::api_error::ApiError, util::time},
     auth::models::access_token_claims::AccessTokenClaims,
+    notifications,
+    realtime::{self, enums::realtime_event::RealtimeEvent},
+    settings,
+    webhooks::{self, enums::webhook_event::WebhookEvent},
     AppState,
 };
 
@@ -11,6 +17,7 @@ use super::{
     dtos::{
         create_memo_dto::CreateMemoDto, edit_memo_dto::EditMemoDto, get_memos_dto::GetMemosDto,
     },
+    enums::memo_status::MemoStatus,
     models::memo::Memo,
 };
 
@@ -19,31 +26,42 @@ pub async fn create_memo(
     claims: &AccessTokenClaims,
     state: &AppState,
 ) -> Result<Memo, ApiError> {
-    let memo = Memo::new(dto, claims);
+    let settings = settings::service::get_user_settings(&claims.sub, state).await?;
+    let memo = Memo::new(dto, &settings, claims);
 
     let sqlx_result = sqlx::query(
         "
         INSERT INTO memos
-        (id, user_id, title, description, priority, status, visibility, frequency, trigger_at, updated_at, created_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        (id, user_id, title, description, priority, status, visibility, frequency, notification_channels, trigger_at, updated_at, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
         ",
     )
-    .bind(&memo.id)
-    .bind(&claims.id)
+    .bind(memo.id)
+    .bind(memo.user_id)
     .bind(&memo.title)
     .bind(&memo.description)
-    .bind(&memo.priority)
+    .bind(memo.priority)
     .bind(&memo.status)
-    .bind(&memo.visibility)
+    .bind(memo.visibility)
     .bind(&memo.frequency)
-    .bind(&memo.trigger_at)
-    .bind(&memo.updated_at)
-    .bind(&memo.created_at)
+    .bind(&memo.notification_channels)
+    .bind(memo.trigger_at)
+    .bind(memo.updated_at)
+    .bind(memo.created_at)
     .execute(&state.pool)
     .await;
 
     match sqlx_result {
-        Ok(_) => Ok(memo),
+        Ok(_) => {
+            webhooks::service::dispatch(&claims.sub, WebhookEvent::MEMO_CREATED, &memo, state);
+            notify_memo_change(
+                RealtimeEvent::MEMO_CREATED,
+                &memo.id.to_string(),
+                claims,
+                state,
+            );
+            Ok(memo)
+        }
         Err(e) => {
             tracing::error!(%e);
             Err(ApiError::new(
@@ -130,10 +148,10 @@ pub async fn get_memos(
     let mut sqlx = sqlx::query_as::<Postgres, Memo>(&query);
 
     if let Some(id) = &dto.id {
-        sqlx = sqlx.bind(id);
+        sqlx = sqlx.bind(Uuid::from_str(id).unwrap_or_default());
     }
     if let Some(user_id) = &dto.user_id {
-        sqlx = sqlx.bind(user_id);
+        sqlx = sqlx.bind(Uuid::from_str(user_id).unwrap_or_default());
     }
     if let Some(search) = &dto.search {
         sqlx = sqlx.bind(search);
@@ -173,7 +191,7 @@ pub async fn get_memo(
         WHERE id = $1
         ",
     )
-    .bind(id)
+    .bind(Uuid::from_str(id).unwrap_or_default())
     .fetch_optional(&state.pool)
     .await;
 
@@ -226,6 +244,10 @@ pub async fn edit_memo(
         index += 1;
         query.push_str(&format!("frequency = ${}, ", index));
     }
+    if dto.notification_channels.is_some() {
+        index += 1;
+        query.push_str(&format!("notification_channels = ${}, ", index));
+    }
     if dto.trigger_at.is_some() {
         index += 1;
         query.push_str(&format!("trigger_at = ${}, ", index));
@@ -260,18 +282,42 @@ pub async fn edit_memo(
     if let Some(frequency) = &dto.frequency {
         sqlx = sqlx.bind(frequency);
     }
+    // an empty list falls back to the user's notification channels
+    if let Some(notification_channels) = &dto.notification_channels {
+        sqlx = sqlx.bind(
+            Some(notification_channels)
+                .filter(|notification_channels| !notification_channels.is_empty()),
+        );
+    }
     if let Some(trigger_at) = &dto.trigger_at {
         sqlx = sqlx.bind(trigger_at);
     }
     sqlx = sqlx.bind(time::current_time_in_millis());
-    sqlx = sqlx.bind(id);
-    sqlx = sqlx.bind(&claims.id);
+    sqlx = sqlx.bind(Uuid::from_str(id).unwrap_or_default());
+    sqlx = sqlx.bind(Uuid::from_str(&claims.sub).unwrap_or_default());
 
     let sqlx_result = sqlx.fetch_optional(&state.pool).await;
 
     match sqlx_result {
         Ok(data) => match data {
-            Some(memo) => Ok(memo),
+            Some(memo) => {
+                webhooks::service::dispatch(&claims.sub, WebhookEvent::MEMO_UPDATED, &memo, state);
+                notify_memo_change(
+                    RealtimeEvent::MEMO_UPDATED,
+                    &memo.id.to_string(),
+                    claims,
+                    state,
+                );
+                if dto.status.as_deref() == Some(MemoStatus::COMPLETED) {
+                    webhooks::service::dispatch(
+                        &claims.sub,
+                        WebhookEvent::MEMO_COMPLETED,
+                        &memo,
+                        state,
+                    );
+                }
+                Ok(memo)
+            }
             None => Err(ApiError::new(StatusCode::NOT_FOUND, "Memo not found.")),
         },
         Err(e) => {
@@ -283,3 +329,187 @@ pub async fn edit_memo(
         }
     }
 }
+
+pub async fn delete_memo(
+    id: &str,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        DELETE FROM memos
+        WHERE id = $1 AND user_id = $2
+        ",
+    )
+    .bind(Uuid::from_str(id).unwrap_or_default())
+    .bind(Uuid::from_str(&claims.sub).unwrap_or_default())
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => {
+                webhooks::service::dispatch(
+                    &claims.sub,
+                    WebhookEvent::MEMO_DELETED,
+                    &serde_json::json!({ "id": id }),
+                    state,
+                );
+                notify_memo_change(RealtimeEvent::MEMO_DELETED, id, claims, state);
+                Ok(())
+            }
+            false => Err(ApiError::new(StatusCode::NOT_FOUND, "Memo not found.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to delete memo.",
+            ))
+        }
+    }
+}
+
+// marks due memos as notified so each trigger_at fires once, even across instances
+pub async fn claim_due_memos(limit: i64, state: &AppState) -> Result<Vec<Memo>, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, Memo>(
+        "
+        UPDATE memos SET notified_trigger_at = memos.trigger_at
+        FROM (
+            SELECT memos.id FROM memos
+            LEFT JOIN user_settings ON user_settings.user_id = memos.user_id
+            WHERE memos.status = 'pending'
+            AND memos.notified_trigger_at IS DISTINCT FROM memos.trigger_at
+            AND memos.trigger_at - COALESCE(user_settings.reminder_lead_time, 0)::BIGINT * 60000 <= $1
+            ORDER BY memos.trigger_at ASC
+            LIMIT $2
+            FOR UPDATE OF memos SKIP LOCKED
+        ) due
+        WHERE memos.id = due.id
+        RETURNING memos.*
+        ",
+    )
+    .bind(time::current_time_in_millis())
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(memos) => Ok(memos),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get due memos.",
+            ))
+        }
+    }
+}
+
+pub async fn get_pending_memos_before(
+    user_id: &Uuid,
+    before: i64,
+    limit: i64,
+    state: &AppState,
+) -> Result<Vec<Memo>, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, Memo>(
+        "
+        SELECT * FROM memos
+        WHERE user_id = $1 AND status = 'pending' AND trigger_at < $2
+        ORDER BY trigger_at DESC
+        LIMIT $3
+        ",
+    )
+    .bind(user_id)
+    .bind(before)
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(memos) => Ok(memos),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get memos.",
+            ))
+        }
+    }
+}
+
+// lets the user's other clients know, over open streams and silent pushes
+fn notify_memo_change(event: &str, id: &str, claims: &AccessTokenClaims, state: &AppState) {
+    realtime::service::publish(&claims.sub, event, id, state);
+    notifications::service::sync_devices(&claims.sub, claims.sid.as_deref(), event, id, state);
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    use crate::app::test_util;
+
+    use super::*;
+
+    #[sqlx::test(migrations = false)]
+    async fn creates_gets_edits_and_deletes_memo(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let claims = test_util::access_claims(&test_util::insert_user("someone", &state).await);
+        let id = Uuid::new_v4().to_string();
+
+        let dto = serde_json::from_value(json!({
+            "id": id,
+            "title": "water the plants",
+            "trigger_at": 0,
+        }))
+        .unwrap();
+        create_memo(&dto, &claims, &state).await.unwrap();
+        assert_eq!(
+            get_memo(&id, &claims, &state).await.unwrap().title,
+            "water the plants"
+        );
+
+        let dto = serde_json::from_value(json!({ "title": "water the cactus" })).unwrap();
+        let memo = edit_memo(&id, &dto, &claims, &state).await.unwrap();
+        assert_eq!(memo.title, "water the cactus");
+
+        let dto = serde_json::from_value(json!({ "user_id": claims.sub })).unwrap();
+        assert_eq!(
+            get_memos(&dto, Some(&claims), &state).await.unwrap().len(),
+            1
+        );
+
+        delete_memo(&id, &claims, &state).await.unwrap();
+        let e = get_memo(&id, &claims, &state).await.unwrap_err();
+        assert_eq!(e.code, StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn rejects_edit_and_delete_by_other_user(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let claims = test_util::access_claims(&test_util::insert_user("someone", &state).await);
+        let other_claims =
+            test_util::access_claims(&test_util::insert_user("someoneelse", &state).await);
+        let id = Uuid::new_v4().to_string();
+
+        let dto = serde_json::from_value(json!({
+            "id": id,
+            "title": "water the plants",
+            "trigger_at": 0,
+        }))
+        .unwrap();
+        create_memo(&dto, &claims, &state).await.unwrap();
+
+        let dto = serde_json::from_value(json!({ "title": "water the cactus" })).unwrap();
+        let e = edit_memo(&id, &dto, &other_claims, &state)
+            .await
+            .unwrap_err();
+        assert_eq!(e.code, StatusCode::NOT_FOUND);
+        let e = delete_memo(&id, &other_claims, &state).await.unwrap_err();
+        assert_eq!(e.code, StatusCode::NOT_FOUND);
+        let e = get_memo("not-a-uuid", &claims, &state).await.unwrap_err();
+        assert_eq!(e.code, StatusCode::NOT_FOUND);
+    }
+}
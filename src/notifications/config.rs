@@ -0,0 +1,2 @@
+pub static NOTIFICATION_LEASE: u64 = 300;
+pub static NOTIFICATION_FLUSH_LIMIT: i64 = 500;
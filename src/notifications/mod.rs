@@ -0,0 +1,4 @@
+pub mod config;
+pub mod models;
+pub mod service;
+pub mod util;
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::{app, memos::models::memo::Memo};
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct DeferredNotification {
+    pub id: sqlx::types::Uuid,
+    pub user_id: sqlx::types::Uuid,
+    pub memo_id: sqlx::types::Uuid,
+    pub title: String,
+    pub body: String,
+    pub notification_channels: Vec<String>,
+    pub deliver_at: i64,
+    pub created_at: i64,
+}
+
+impl DeferredNotification {
+    pub fn new(memo: &Memo, notification_channels: &[String], deliver_at: i64) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id: memo.user_id,
+            memo_id: memo.id,
+            title: memo.title.to_string(),
+            body: memo.description.clone().unwrap_or_default(),
+            notification_channels: notification_channels.to_vec(),
+            deliver_at,
+            created_at: app::util::time::current_time_in_millis(),
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod deferred_notification;
@@ -0,0 +1,342 @@
+use std::collections::{BTreeMap, HashMap};
+
+use axum::http::StatusCode;
+use sqlx::Postgres;
+
+use crate::{
+    app::{
+        fcm::models::fcm_message::FcmMessage,
+        models::{api_error::ApiError, app_state::AppState},
+        util::time,
+    },
+    devices::{self, dtos::get_devices_filter_dto::GetDevicesFilterDto},
+    mail::{self, templates::reminder_template},
+    memos::{enums::memo_priority::MemoPriority, models::memo::Memo},
+    settings::{self, enums::notification_channel::NotificationChannel},
+    users,
+};
+
+use super::{
+    config::{NOTIFICATION_FLUSH_LIMIT, NOTIFICATION_LEASE},
+    models::deferred_notification::DeferredNotification,
+    util::quiet_hours,
+};
+
+pub async fn notify_memo(memo: &Memo, state: &AppState) -> Result<(), ApiError> {
+    let user_id = memo.user_id.to_string();
+    let user_settings = settings::service::get_user_settings(&user_id, state).await?;
+
+    let notification_channels = match &memo.notification_channels {
+        Some(notification_channels) => notification_channels,
+        None => &user_settings.notification_channels,
+    };
+
+    let bypass = memo.priority >= MemoPriority::HIGH && user_settings.quiet_hours_bypass;
+    if !bypass {
+        let current_time = time::current_time_in_millis();
+        if let Some(deliver_at) = quiet_hours::end_of_quiet_hours(&user_settings, current_time) {
+            let deferred_notification =
+                DeferredNotification::new(memo, notification_channels, deliver_at);
+            return create_deferred_notification(&deferred_notification, state).await;
+        }
+    }
+
+    deliver(
+        &user_id,
+        notification_channels,
+        &memo.title,
+        memo.description.as_deref().unwrap_or_default(),
+        state,
+    )
+    .await
+}
+
+// notifications deferred for the same user are sent as one batch; rows are leased while
+// delivering and only deleted once the batch went out, so a failure retries after the lease
+pub async fn flush_deferred_notifications(state: &AppState) -> Result<(), ApiError> {
+    let current_time = time::current_time_in_millis();
+
+    let sqlx_result = sqlx::query_as::<Postgres, DeferredNotification>(
+        "
+        UPDATE deferred_notifications SET deliver_at = $1
+        FROM (
+            SELECT id FROM deferred_notifications
+            WHERE deliver_at <= $2
+            ORDER BY deliver_at ASC
+            LIMIT $3
+            FOR UPDATE SKIP LOCKED
+        ) due
+        WHERE deferred_notifications.id = due.id
+        RETURNING deferred_notifications.*
+        ",
+    )
+    .bind(current_time + (NOTIFICATION_LEASE * 1000) as i64)
+    .bind(current_time)
+    .bind(NOTIFICATION_FLUSH_LIMIT)
+    .fetch_all(&state.pool)
+    .await;
+
+    let deferred_notifications = match sqlx_result {
+        Ok(deferred_notifications) => deferred_notifications,
+        Err(e) => {
+            tracing::error!(%e);
+            return Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get deferred notifications.",
+            ));
+        }
+    };
+
+    let mut batches: BTreeMap<String, Vec<DeferredNotification>> = BTreeMap::new();
+    for deferred_notification in deferred_notifications {
+        batches
+            .entry(deferred_notification.user_id.to_string())
+            .or_default()
+            .push(deferred_notification);
+    }
+
+    for (user_id, mut batch) in batches {
+        batch.sort_by_key(|deferred_notification| deferred_notification.created_at);
+
+        let mut notification_channels: Vec<String> = batch
+            .iter()
+            .flat_map(|deferred_notification| deferred_notification.notification_channels.clone())
+            .collect();
+        notification_channels.sort();
+        notification_channels.dedup();
+
+        let ids: Vec<sqlx::types::Uuid> = batch
+            .iter()
+            .map(|deferred_notification| deferred_notification.id)
+            .collect();
+
+        let deliver_result = match batch.len() {
+            1 => {
+                deliver(
+                    &user_id,
+                    &notification_channels,
+                    &batch[0].title,
+                    &batch[0].body,
+                    state,
+                )
+                .await
+            }
+            count => {
+                let titles: Vec<&str> = batch
+                    .iter()
+                    .map(|deferred_notification| deferred_notification.title.as_str())
+                    .collect();
+                let title = format!("{} reminders", count);
+                deliver(
+                    &user_id,
+                    &notification_channels,
+                    &title,
+                    &titles.join(", "),
+                    state,
+                )
+                .await
+            }
+        };
+
+        match deliver_result {
+            Ok(_) => {
+                let _ = delete_deferred_notifications(&ids, state).await;
+            }
+            Err(e) => tracing::error!(
+                "failed to deliver deferred notifications for user {}: {}",
+                user_id,
+                e.message
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+async fn delete_deferred_notifications(
+    ids: &[sqlx::types::Uuid],
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query("DELETE FROM deferred_notifications WHERE id = ANY($1)")
+        .bind(ids)
+        .execute(&state.pool)
+        .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to delete deferred notifications.",
+            ))
+        }
+    }
+}
+
+// push falls back to email when no device has a messaging token
+pub async fn deliver(
+    user_id: &str,
+    notification_channels: &[String],
+    title: &str,
+    body: &str,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let has_channel = |notification_channel: &str| {
+        notification_channels
+            .iter()
+            .any(|value| value == notification_channel)
+    };
+
+    let mut pushed = false;
+    if has_channel(NotificationChannel::PUSH) {
+        pushed = deliver_push(user_id, title, body, state).await?;
+    }
+
+    if has_channel(NotificationChannel::EMAIL)
+        || (has_channel(NotificationChannel::PUSH) && !pushed)
+    {
+        deliver_email(user_id, title, body, state).await?;
+    }
+
+    Ok(())
+}
+
+// silent push so the user's other devices pull the change, the device that made it is skipped
+pub fn sync_devices(
+    user_id: &str,
+    device_id: Option<&str>,
+    event: &str,
+    memo_id: &str,
+    state: &AppState,
+) {
+    let dto = GetDevicesFilterDto {
+        id: None,
+        user_id: Some(user_id.to_string()),
+        sort: None,
+        cursor: None,
+        limit: Some(100),
+    };
+    let device_id = device_id.map(|device_id| device_id.to_string());
+    let data = HashMap::from([
+        ("type".to_string(), "sync".to_string()),
+        ("event".to_string(), event.to_string()),
+        ("memo_id".to_string(), memo_id.to_string()),
+    ]);
+    let state = state.clone();
+
+    tokio::spawn(async move {
+        let Ok(devices) = devices::service::get_devices(&dto, None, &state).await else {
+            return;
+        };
+
+        let _fcm_client = state.authman.fcm_client(&state.http_client).await;
+        let fcm_client = _fcm_client.read().await;
+
+        for device in devices {
+            if device_id.as_deref() == Some(device.id.to_string().as_str()) {
+                continue;
+            }
+            let Some(messaging_token) = device.messaging_token else {
+                continue;
+            };
+
+            let message = FcmMessage {
+                token: messaging_token,
+                title: None,
+                body: None,
+                click_action: None,
+                data: Some(data.clone()),
+            };
+
+            let _ = fcm_client.send(message, &state.http_client).await;
+        }
+    });
+}
+
+async fn deliver_email(
+    user_id: &str,
+    title: &str,
+    body: &str,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let user = users::service::get_user_by_id(user_id, state).await?;
+    let mail_template = reminder_template::new(title, body);
+
+    mail::service::send(&user.email, &mail_template.0, &mail_template.1, &state.envy).await
+}
+
+async fn deliver_push(
+    user_id: &str,
+    title: &str,
+    body: &str,
+    state: &AppState,
+) -> Result<bool, ApiError> {
+    let dto = GetDevicesFilterDto {
+        id: None,
+        user_id: Some(user_id.to_string()),
+        sort: None,
+        cursor: None,
+        limit: Some(100),
+    };
+    let devices = devices::service::get_devices(&dto, None, state).await?;
+
+    let _fcm_client = state.authman.fcm_client(&state.http_client).await;
+    let fcm_client = _fcm_client.read().await;
+
+    let mut pushed = false;
+    for device in devices {
+        let Some(messaging_token) = device.messaging_token else {
+            continue;
+        };
+
+        let message = FcmMessage {
+            token: messaging_token,
+            title: Some(title.to_string()),
+            body: Some(body.to_string()),
+            click_action: None,
+            data: None,
+        };
+
+        if fcm_client.send(message, &state.http_client).await.is_ok() {
+            pushed = true;
+        }
+    }
+
+    Ok(pushed)
+}
+
+async fn create_deferred_notification(
+    deferred_notification: &DeferredNotification,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO deferred_notifications (
+            id, user_id, memo_id, title, body, notification_channels, deliver_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ",
+    )
+    .bind(deferred_notification.id)
+    .bind(deferred_notification.user_id)
+    .bind(deferred_notification.memo_id)
+    .bind(&deferred_notification.title)
+    .bind(&deferred_notification.body)
+    .bind(&deferred_notification.notification_channels)
+    .bind(deferred_notification.deliver_at)
+    .bind(deferred_notification.created_at)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to defer notification.",
+            ))
+        }
+    }
+}
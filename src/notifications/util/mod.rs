@@ -0,0 +1 @@
+pub mod quiet_hours;
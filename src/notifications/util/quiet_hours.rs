@@ -0,0 +1,37 @@
+use std::str::FromStr;
+
+use chrono::{TimeZone, Timelike};
+use chrono_tz::Tz;
+
+use crate::settings::models::user_settings::UserSettings;
+
+// returns when the current quiet window ends, or None outside of quiet hours
+pub fn end_of_quiet_hours(user_settings: &UserSettings, time_in_millis: i64) -> Option<i64> {
+    let (Some(start), Some(end)) = (
+        user_settings.quiet_hours_start,
+        user_settings.quiet_hours_end,
+    ) else {
+        return None;
+    };
+    if start == end {
+        return None;
+    }
+
+    let timezone = Tz::from_str(&user_settings.timezone).unwrap_or(Tz::UTC);
+    let local_time = timezone.timestamp_millis_opt(time_in_millis).single()?;
+    let minute = (local_time.hour() * 60 + local_time.minute()) as i16;
+
+    let quiet = match start < end {
+        true => minute >= start && minute < end,
+        false => minute >= start || minute < end,
+    };
+    if !quiet {
+        return None;
+    }
+
+    let start_of_minute =
+        time_in_millis - (local_time.second() as i64 * 1000) - (time_in_millis.rem_euclid(1000));
+    let minutes_left = (end - minute).rem_euclid(1440) as i64;
+
+    Some(start_of_minute + minutes_left * 60000)
+}
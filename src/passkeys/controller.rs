@@ -0,0 +1,60 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use validator::Validate;
+
+use crate::{
+    app::models::{api_error::ApiError, app_state::AppState},
+    auth::{enums::scope::Scope, models::access_token_claims::ExtractClaims},
+};
+
+use super::{
+    dtos::create_passkey_dto::CreatePasskeyDto,
+    models::{passkey::Passkey, passkey_options::PasskeyOptions},
+    service,
+};
+
+pub async fn request_passkey_registration(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<Json<PasskeyOptions>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    match service::request_passkey_registration(&claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn create_passkey(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+    Json(dto): Json<CreatePasskeyDto>,
+) -> Result<Json<Passkey>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    dto.validate()?;
+    match service::create_passkey(&dto, &claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_passkeys(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<Json<Vec<Passkey>>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    match service::get_passkeys(&claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn delete_passkey(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<(), ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    service::delete_passkey(&id, &claims, &state).await
+}
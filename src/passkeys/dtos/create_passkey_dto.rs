@@ -0,0 +1,16 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreatePasskeyDto {
+    pub challenge_id: String,
+    #[validate(length(
+        min = 1,
+        max = 64,
+        message = "name must be between 1 and 64 characters."
+    ))]
+    pub name: Option<String>,
+    pub credential_id: String,
+    pub client_data_json: String,
+    pub attestation_object: String,
+}
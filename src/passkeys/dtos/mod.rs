@@ -0,0 +1 @@
+pub mod create_passkey_dto;
@@ -0,0 +1,3 @@
+pub mod passkey;
+pub mod passkey_options;
+pub mod webauthn_challenge;
@@ -0,0 +1,47 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::{app, auth::util::webauthn::AttestedCredential};
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Passkey {
+    pub id: sqlx::types::Uuid,
+    pub user_id: sqlx::types::Uuid,
+    pub credential_id: String,
+    pub name: String,
+    #[serde(skip_serializing)]
+    pub public_key: String,
+    #[serde(skip_serializing)]
+    pub algorithm: i64,
+    #[serde(skip_serializing)]
+    pub sign_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<i64>,
+    pub updated_at: i64,
+    pub created_at: i64,
+}
+
+impl Passkey {
+    pub fn new(user_id: &str, name: &Option<String>, credential: AttestedCredential) -> Self {
+        let current_time = app::util::time::current_time_in_millis();
+
+        Self {
+            id: Uuid::new_v4(),
+            user_id: Uuid::from_str(user_id).unwrap(),
+            credential_id: credential.credential_id,
+            name: match name {
+                Some(name) => name.trim().to_string(),
+                None => "Passkey".to_string(),
+            },
+            public_key: credential.public_key,
+            algorithm: credential.algorithm,
+            sign_count: credential.sign_count,
+            last_used_at: None,
+            updated_at: current_time,
+            created_at: current_time,
+        }
+    }
+}
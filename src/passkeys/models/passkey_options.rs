@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasskeyOptions {
+    pub challenge_id: String,
+    pub challenge: String,
+    pub rp_id: String,
+    pub rp_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<String>,
+    pub algorithms: Vec<i64>,
+    pub exclude_credentials: Vec<String>,
+    pub timeout: u64,
+}
@@ -0,0 +1,35 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    app,
+    auth::{config::WEBAUTHN_CHALLENGE_EXP, util::webauthn},
+};
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct WebauthnChallenge {
+    pub id: sqlx::types::Uuid,
+    pub user_id: Option<sqlx::types::Uuid>,
+    pub challenge: String,
+    pub ceremony: String,
+    pub expires_at: i64,
+    pub created_at: i64,
+}
+
+impl WebauthnChallenge {
+    pub fn new(user_id: Option<&str>, ceremony: &str) -> Self {
+        let current_time = app::util::time::current_time_in_millis();
+
+        Self {
+            id: Uuid::new_v4(),
+            user_id: user_id.map(|user_id| Uuid::from_str(user_id).unwrap()),
+            challenge: webauthn::new_challenge(),
+            ceremony: ceremony.to_string(),
+            expires_at: current_time + (WEBAUTHN_CHALLENGE_EXP * 1000) as i64,
+            created_at: current_time,
+        }
+    }
+}
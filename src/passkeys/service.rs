@@ -0,0 +1,456 @@
+use std::str::FromStr;
+
+use axum::http::StatusCode;
+use sqlx::{types::Uuid, Postgres};
+
+use crate::{
+    app::{
+        self,
+        models::{api_error::ApiError, app_state::AppState},
+        util::time,
+    },
+    auth::{
+        config::WEBAUTHN_CHALLENGE_EXP,
+        enums::webauthn_ceremony::WebauthnCeremony,
+        models::access_token_claims::AccessTokenClaims,
+        util::webauthn::{self, ALG_ES256, ALG_RS256},
+    },
+    users,
+};
+
+use super::{
+    dtos::create_passkey_dto::CreatePasskeyDto,
+    models::{
+        passkey::Passkey, passkey_options::PasskeyOptions, webauthn_challenge::WebauthnChallenge,
+    },
+};
+
+pub async fn request_passkey_registration(
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<PasskeyOptions, ApiError> {
+    let user = users::service::get_user_by_id(&claims.sub, state).await?;
+    let passkeys = get_passkeys(claims, state).await?;
+
+    let challenge = WebauthnChallenge::new(Some(&claims.sub), WebauthnCeremony::REGISTRATION);
+    create_webauthn_challenge(&challenge, state).await?;
+
+    Ok(PasskeyOptions {
+        challenge_id: challenge.id.to_string(),
+        challenge: challenge.challenge,
+        rp_id: app::config::WEBAUTHN_RP_ID.to_string(),
+        rp_name: app::config::APP_NAME.to_string(),
+        user_id: Some(user.id.to_string()),
+        user_name: Some(user.username),
+        algorithms: vec![ALG_ES256, ALG_RS256],
+        exclude_credentials: passkeys
+            .into_iter()
+            .map(|passkey| passkey.credential_id)
+            .collect(),
+        timeout: WEBAUTHN_CHALLENGE_EXP * 1000,
+    })
+}
+
+pub async fn request_passkey_authentication(state: &AppState) -> Result<PasskeyOptions, ApiError> {
+    let challenge = WebauthnChallenge::new(None, WebauthnCeremony::AUTHENTICATION);
+    create_webauthn_challenge(&challenge, state).await?;
+
+    Ok(PasskeyOptions {
+        challenge_id: challenge.id.to_string(),
+        challenge: challenge.challenge,
+        rp_id: app::config::WEBAUTHN_RP_ID.to_string(),
+        rp_name: app::config::APP_NAME.to_string(),
+        user_id: None,
+        user_name: None,
+        algorithms: vec![ALG_ES256, ALG_RS256],
+        exclude_credentials: Vec::new(),
+        timeout: WEBAUTHN_CHALLENGE_EXP * 1000,
+    })
+}
+
+async fn create_webauthn_challenge(
+    challenge: &WebauthnChallenge,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let _ = sqlx::query("DELETE FROM webauthn_challenges WHERE expires_at <= $1")
+        .bind(time::current_time_in_millis())
+        .execute(&state.pool)
+        .await;
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO webauthn_challenges (
+            id, user_id, challenge, ceremony, expires_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ",
+    )
+    .bind(challenge.id)
+    .bind(challenge.user_id)
+    .bind(&challenge.challenge)
+    .bind(&challenge.ceremony)
+    .bind(challenge.expires_at)
+    .bind(challenge.created_at)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to create challenge.",
+            ))
+        }
+    }
+}
+
+pub async fn consume_webauthn_challenge(
+    id: &str,
+    ceremony: &str,
+    state: &AppState,
+) -> Result<WebauthnChallenge, ApiError> {
+    let Ok(id) = Uuid::from_str(id) else {
+        return Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "Invalid or expired challenge.",
+        ));
+    };
+
+    let sqlx_result = sqlx::query_as::<Postgres, WebauthnChallenge>(
+        "
+        DELETE FROM webauthn_challenges
+        WHERE id = $1 AND ceremony = $2 AND expires_at > $3
+        RETURNING *
+        ",
+    )
+    .bind(id)
+    .bind(ceremony)
+    .bind(time::current_time_in_millis())
+    .fetch_optional(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(challenge) => match challenge {
+            Some(challenge) => Ok(challenge),
+            None => Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "Invalid or expired challenge.",
+            )),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get challenge.",
+            ))
+        }
+    }
+}
+
+pub async fn create_passkey(
+    dto: &CreatePasskeyDto,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<Passkey, ApiError> {
+    let challenge =
+        consume_webauthn_challenge(&dto.challenge_id, WebauthnCeremony::REGISTRATION, state)
+            .await?;
+    if challenge.user_id.map(|user_id| user_id.to_string()) != Some(claims.sub.to_string()) {
+        return Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "Invalid or expired challenge.",
+        ));
+    }
+
+    let credential = match webauthn::verify_registration(
+        &dto.client_data_json,
+        &dto.attestation_object,
+        &challenge.challenge,
+    ) {
+        Ok(credential) => credential,
+        Err(e) => {
+            tracing::warn!("{}", e.message);
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "Invalid passkey registration.",
+            ));
+        }
+    };
+    if credential.credential_id != dto.credential_id.trim_end_matches('=') {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "Invalid passkey registration.",
+        ));
+    }
+
+    let passkey = Passkey::new(&claims.sub, &dto.name, credential);
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO passkeys (
+            id, user_id, credential_id, name, public_key, algorithm,
+            sign_count, last_used_at, updated_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        ",
+    )
+    .bind(passkey.id)
+    .bind(passkey.user_id)
+    .bind(&passkey.credential_id)
+    .bind(&passkey.name)
+    .bind(&passkey.public_key)
+    .bind(passkey.algorithm)
+    .bind(passkey.sign_count)
+    .bind(passkey.last_used_at)
+    .bind(passkey.updated_at)
+    .bind(passkey.created_at)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(passkey),
+        Err(e) => {
+            let Some(db_err) = e.as_database_error() else {
+                tracing::error!(%e);
+                return Err(ApiError::internal_server_error());
+            };
+            let Some(code) = app::util::sqlx::extract_db_err_code(db_err) else {
+                tracing::error!(%e);
+                return Err(ApiError::internal_server_error());
+            };
+
+            match code.as_str() {
+                app::util::sqlx::SqlStateCodes::UNIQUE_VIOLATION => Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    "Passkey already exists.",
+                )),
+                _ => {
+                    tracing::error!(%e);
+                    Err(ApiError::new(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Failed to create passkey.",
+                    ))
+                }
+            }
+        }
+    }
+}
+
+pub async fn get_passkeys(
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<Vec<Passkey>, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, Passkey>(
+        "
+        SELECT * FROM passkeys
+        WHERE user_id = $1
+        ORDER BY created_at DESC
+        ",
+    )
+    .bind(Uuid::from_str(&claims.sub).unwrap_or_default())
+    .fetch_all(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(passkeys) => Ok(passkeys),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get passkeys.",
+            ))
+        }
+    }
+}
+
+pub async fn get_passkey_by_credential_id(
+    credential_id: &str,
+    state: &AppState,
+) -> Result<Passkey, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, Passkey>(
+        "
+        SELECT * FROM passkeys
+        WHERE credential_id = $1
+        ",
+    )
+    .bind(credential_id.trim_end_matches('='))
+    .fetch_optional(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(passkey) => match passkey {
+            Some(passkey) => Ok(passkey),
+            None => Err(ApiError::new(StatusCode::NOT_FOUND, "Passkey not found.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get passkey.",
+            ))
+        }
+    }
+}
+
+pub async fn edit_passkey_sign_count(
+    passkey: &Passkey,
+    sign_count: i64,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let current_time = time::current_time_in_millis();
+
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE passkeys SET sign_count = $1, last_used_at = $2, updated_at = $2
+        WHERE id = $3 AND sign_count = $4
+        ",
+    )
+    .bind(sign_count)
+    .bind(current_time)
+    .bind(passkey.id)
+    .bind(passkey.sign_count)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "Invalid credentials.",
+            )),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to edit passkey.",
+            ))
+        }
+    }
+}
+
+pub async fn delete_passkey(
+    id: &str,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let Ok(id) = Uuid::from_str(id) else {
+        return Err(ApiError::new(StatusCode::NOT_FOUND, "Passkey not found."));
+    };
+
+    let sqlx_result = sqlx::query(
+        "
+        DELETE FROM passkeys
+        WHERE id = $1 AND user_id = $2
+        ",
+    )
+    .bind(id)
+    .bind(Uuid::from_str(&claims.sub).unwrap_or_default())
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(ApiError::new(StatusCode::NOT_FOUND, "Passkey not found.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to delete passkey.",
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+
+    use crate::app::test_util;
+
+    use super::*;
+
+    #[sqlx::test(migrations = false)]
+    async fn creates_and_consumes_challenge_once(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let user_id = test_util::insert_user("someone", &state).await;
+        let claims = test_util::access_claims(&user_id);
+
+        let options = request_passkey_registration(&claims, &state).await.unwrap();
+        assert!(options.exclude_credentials.is_empty());
+
+        let e = consume_webauthn_challenge(
+            &options.challenge_id,
+            WebauthnCeremony::AUTHENTICATION,
+            &state,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(e.code, StatusCode::UNAUTHORIZED);
+
+        let challenge = consume_webauthn_challenge(
+            &options.challenge_id,
+            WebauthnCeremony::REGISTRATION,
+            &state,
+        )
+        .await
+        .unwrap();
+        assert_eq!(challenge.user_id, Some(user_id));
+        assert_eq!(challenge.challenge, options.challenge);
+
+        let e = consume_webauthn_challenge(
+            &options.challenge_id,
+            WebauthnCeremony::REGISTRATION,
+            &state,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(e.code, StatusCode::UNAUTHORIZED);
+
+        let e = consume_webauthn_challenge("not-a-uuid", WebauthnCeremony::REGISTRATION, &state)
+            .await
+            .unwrap_err();
+        assert_eq!(e.code, StatusCode::UNAUTHORIZED);
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn lists_and_deletes_passkeys(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let user_id = test_util::insert_user("someone", &state).await;
+        let claims = test_util::access_claims(&user_id);
+        let id = Uuid::new_v4();
+        sqlx::query(
+            "
+            INSERT INTO passkeys (
+                id, user_id, credential_id, name, public_key, algorithm, sign_count,
+                updated_at, created_at
+            )
+            VALUES ($1, $2, 'credential', 'Passkey', 'key', -7, 0, 0, 0)
+            ",
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&state.pool)
+        .await
+        .unwrap();
+
+        let passkeys = get_passkeys(&claims, &state).await.unwrap();
+        assert_eq!(passkeys.len(), 1);
+        assert_eq!(passkeys[0].id, id);
+
+        let e = delete_passkey("not-a-uuid", &claims, &state)
+            .await
+            .unwrap_err();
+        assert_eq!(e.code, StatusCode::NOT_FOUND);
+
+        delete_passkey(&id.to_string(), &claims, &state)
+            .await
+            .unwrap();
+        assert!(get_passkeys(&claims, &state).await.unwrap().is_empty());
+    }
+}
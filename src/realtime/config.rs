@@ -0,0 +1,5 @@
+pub static REALTIME_CHANNEL: &str = "memo_changes";
+pub static REALTIME_CAPACITY: usize = 1024;
+pub static REALTIME_KEEP_ALIVE: u64 = 15;
+pub static REALTIME_MAX_DURATION: u64 = 3600;
+pub static REALTIME_RECONNECT_DELAY: u64 = 5;
@@ -0,0 +1,23 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::State,
+    response::sse::{Event, Sse},
+};
+use futures::Stream;
+
+use crate::{
+    app::models::api_error::ApiError,
+    auth::{enums::scope::Scope, models::access_token_claims::ExtractClaims},
+    AppState,
+};
+
+use super::service;
+
+pub async fn get_memo_changes(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    claims.require_scope(Scope::MEMOS_READ)?;
+    Ok(service::stream_memo_changes(&claims, &state))
+}
@@ -0,0 +1 @@
+pub mod realtime_event;
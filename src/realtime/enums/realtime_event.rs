@@ -0,0 +1,9 @@
+#[non_exhaustive]
+pub struct RealtimeEvent;
+
+impl RealtimeEvent {
+    pub const MEMO_CREATED: &'static str = "memo.created";
+    pub const MEMO_UPDATED: &'static str = "memo.updated";
+    pub const MEMO_DELETED: &'static str = "memo.deleted";
+    pub const RESYNC: &'static str = "resync";
+}
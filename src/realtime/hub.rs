@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use tokio::sync::broadcast;
+
+use super::{config::REALTIME_CAPACITY, models::memo_change::MemoChange};
+
+// fans out the changes received by this replica's listener to its open streams
+#[derive(Clone)]
+pub struct RealtimeHub {
+    sender: broadcast::Sender<Arc<MemoChange>>,
+}
+
+impl RealtimeHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(REALTIME_CAPACITY);
+
+        Self { sender }
+    }
+
+    pub fn send(&self, change: MemoChange) {
+        // an error only means nobody is subscribed
+        let _ = self.sender.send(Arc::new(change));
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<MemoChange>> {
+        self.sender.subscribe()
+    }
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use sqlx::postgres::PgListener;
+use tokio::{task, time::sleep};
+
+use crate::app::models::app_state::AppState;
+
+use super::{
+    config::{REALTIME_CHANNEL, REALTIME_RECONNECT_DELAY},
+    models::{memo_change::MemoChange, memo_change_payload::MemoChangePayload},
+    service,
+};
+
+pub fn spawn(state: AppState) {
+    task::spawn(async move {
+        loop {
+            if let Err(e) = listen(&state).await {
+                tracing::error!("realtime listener failed: {}", e);
+            }
+
+            // streams may have missed changes while the listener was down
+            state.realtime.send(MemoChange::resync());
+            sleep(Duration::from_secs(REALTIME_RECONNECT_DELAY)).await;
+        }
+    });
+}
+
+async fn listen(state: &AppState) -> Result<(), sqlx::Error> {
+    let mut listener = PgListener::connect_with(&state.pool).await?;
+    listener.listen(REALTIME_CHANNEL).await?;
+
+    loop {
+        // try_recv reconnects on the next call, but whatever was sent in between is lost
+        let Some(notification) = listener.try_recv().await? else {
+            state.realtime.send(MemoChange::resync());
+            continue;
+        };
+
+        let Ok(payload) = serde_json::from_str::<MemoChangePayload>(notification.payload()) else {
+            continue;
+        };
+        let memo = service::get_changed_memo(&payload, state).await;
+
+        state.realtime.send(MemoChange::new(payload, memo));
+    }
+}
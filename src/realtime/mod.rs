@@ -0,0 +1,7 @@
+pub mod config;
+pub mod controller;
+pub mod enums;
+pub mod hub;
+pub mod listener;
+pub mod models;
+pub mod service;
@@ -0,0 +1,71 @@
+use serde::Serialize;
+
+use crate::{memos::models::memo::Memo, realtime::enums::realtime_event::RealtimeEvent};
+
+use super::memo_change_payload::MemoChangePayload;
+
+// the memo is loaded by each replica from the ids in the payload
+#[derive(Debug, Serialize)]
+pub struct MemoChange {
+    #[serde(skip_serializing)]
+    pub user_id: Option<sqlx::types::Uuid>,
+    pub event: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<sqlx::types::Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<Memo>,
+}
+
+impl MemoChange {
+    pub fn new(payload: MemoChangePayload, memo: Option<Memo>) -> Self {
+        Self {
+            user_id: Some(payload.user_id),
+            event: payload.event,
+            id: Some(payload.id),
+            memo,
+        }
+    }
+
+    // only a resync goes out without a user, and it reaches every subscriber
+    pub fn resync() -> Self {
+        Self {
+            user_id: None,
+            event: RealtimeEvent::RESYNC.to_string(),
+            id: None,
+            memo: None,
+        }
+    }
+
+    pub fn is_visible_to(&self, user_id: &sqlx::types::Uuid) -> bool {
+        match self.user_id {
+            Some(change_user_id) => change_user_id == *user_id,
+            None => self.event == RealtimeEvent::RESYNC,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::realtime::{
+        enums::realtime_event::RealtimeEvent, models::memo_change_payload::MemoChangePayload,
+    };
+
+    use super::MemoChange;
+
+    #[test]
+    fn change_is_only_visible_to_its_user() {
+        let user_id = Uuid::new_v4();
+        let payload = MemoChangePayload::new(user_id, RealtimeEvent::MEMO_CREATED, Uuid::new_v4());
+        let change = MemoChange::new(payload, None);
+
+        assert!(change.is_visible_to(&user_id));
+        assert!(!change.is_visible_to(&Uuid::new_v4()));
+    }
+
+    #[test]
+    fn resync_is_visible_to_everyone() {
+        assert!(MemoChange::resync().is_visible_to(&Uuid::new_v4()));
+    }
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+// what travels through NOTIFY, only ids because of the 8000 byte payload limit
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct MemoChangePayload {
+    pub user_id: sqlx::types::Uuid,
+    pub event: String,
+    pub id: sqlx::types::Uuid,
+}
+
+impl MemoChangePayload {
+    pub fn new(user_id: sqlx::types::Uuid, event: &str, id: sqlx::types::Uuid) -> Self {
+        Self {
+            user_id,
+            event: event.to_string(),
+            id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::realtime::enums::realtime_event::RealtimeEvent;
+
+    use super::MemoChangePayload;
+
+    #[test]
+    fn round_trip_keeps_user_id() {
+        let payload =
+            MemoChangePayload::new(Uuid::new_v4(), RealtimeEvent::MEMO_UPDATED, Uuid::new_v4());
+
+        let json = serde_json::to_string(&payload).unwrap();
+        let decoded = serde_json::from_str::<MemoChangePayload>(&json).unwrap();
+
+        assert_eq!(decoded, payload);
+    }
+
+    #[test]
+    fn rejects_payload_without_user_id() {
+        let json = format!(
+            r#"{{"event":"{}","id":"{}"}}"#,
+            RealtimeEvent::MEMO_UPDATED,
+            Uuid::new_v4()
+        );
+
+        assert!(serde_json::from_str::<MemoChangePayload>(&json).is_err());
+    }
+}
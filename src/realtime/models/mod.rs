@@ -0,0 +1,2 @@
+pub mod memo_change;
+pub mod memo_change_payload;
@@ -0,0 +1,110 @@
+use std::{convert::Infallible, str::FromStr, time::Duration};
+
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::{stream, Stream, StreamExt};
+use sqlx::Postgres;
+use tokio::{
+    sync::broadcast::error::RecvError,
+    time::{sleep_until, Instant},
+};
+use uuid::Uuid;
+
+use crate::{
+    app::{models::app_state::AppState, util::time},
+    auth::models::access_token_claims::AccessTokenClaims,
+    memos::models::memo::Memo,
+};
+
+use super::{
+    config::{REALTIME_CHANNEL, REALTIME_KEEP_ALIVE, REALTIME_MAX_DURATION},
+    enums::realtime_event::RealtimeEvent,
+    models::{memo_change::MemoChange, memo_change_payload::MemoChangePayload},
+};
+
+// goes through postgres so the listeners of every replica receive it
+pub fn publish(user_id: &str, event: &str, id: &str, state: &AppState) {
+    let (Ok(user_id), Ok(id)) = (Uuid::from_str(user_id), Uuid::from_str(id)) else {
+        return;
+    };
+    let Ok(payload) = serde_json::to_string(&MemoChangePayload::new(user_id, event, id)) else {
+        return;
+    };
+    let state = state.clone();
+
+    tokio::spawn(async move {
+        let sqlx_result = sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(REALTIME_CHANNEL)
+            .bind(payload)
+            .execute(&state.pool)
+            .await;
+
+        if let Err(e) = sqlx_result {
+            tracing::error!(%e);
+        }
+    });
+}
+
+pub async fn get_changed_memo(payload: &MemoChangePayload, state: &AppState) -> Option<Memo> {
+    if payload.event == RealtimeEvent::MEMO_DELETED {
+        return None;
+    }
+
+    let sqlx_result = sqlx::query_as::<Postgres, Memo>("SELECT * FROM memos WHERE id = $1")
+        .bind(payload.id)
+        .fetch_optional(&state.pool)
+        .await;
+
+    match sqlx_result {
+        Ok(memo) => memo,
+        Err(e) => {
+            tracing::error!(%e);
+            None
+        }
+    }
+}
+
+// the stream ends when the token expires, clients reconnect with a fresh one
+pub fn stream_memo_changes(
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let user_id = Uuid::from_str(&claims.sub).unwrap_or_default();
+    let receiver = state.realtime.subscribe();
+
+    let current_time = time::current_time_in_secs() as u64;
+    let mut duration = REALTIME_MAX_DURATION;
+    if claims.exp > 0 {
+        duration = duration.min(claims.exp.saturating_sub(current_time));
+    }
+    let deadline = Instant::now() + Duration::from_secs(duration);
+
+    let stream = stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(change) => {
+                    if !change.is_visible_to(&user_id) {
+                        continue;
+                    }
+                    return Some((to_event(&change), receiver));
+                }
+                // this stream fell too far behind and missed changes
+                Err(RecvError::Lagged(_)) => {
+                    return Some((to_event(&MemoChange::resync()), receiver));
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+    .take_until(sleep_until(deadline));
+
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(Duration::from_secs(REALTIME_KEEP_ALIVE)))
+}
+
+fn to_event(change: &MemoChange) -> Result<Event, Infallible> {
+    let event = Event::default().event(&change.event);
+
+    match event.json_data(change) {
+        Ok(event) => Ok(event),
+        Err(_) => Ok(Event::default().event(RealtimeEvent::RESYNC).data("{}")),
+    }
+}
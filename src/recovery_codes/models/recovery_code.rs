@@ -0,0 +1,27 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::app;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct RecoveryCode {
+    pub id: sqlx::types::Uuid,
+    pub user_id: sqlx::types::Uuid,
+    #[serde(skip_serializing)]
+    pub code_hash: String,
+    pub created_at: i64,
+}
+
+impl RecoveryCode {
+    pub fn new(user_id: &str, code_hash: &str) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id: Uuid::from_str(user_id).unwrap(),
+            code_hash: code_hash.to_string(),
+            created_at: app::util::time::current_time_in_millis(),
+        }
+    }
+}
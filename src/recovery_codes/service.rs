@@ -0,0 +1,150 @@
+use std::str::FromStr;
+
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use crate::{
+    app::models::{api_error::ApiError, app_state::AppState},
+    auth::{
+        enums::secret_token_domain::SecretTokenDomain,
+        util::{recovery_code, secret_token},
+    },
+};
+
+use super::models::recovery_code::RecoveryCode;
+
+static RECOVERY_CODES_COUNT: usize = 10;
+
+pub async fn replace_recovery_codes(
+    user_id: &str,
+    state: &AppState,
+) -> Result<Vec<String>, ApiError> {
+    let Ok(user_uuid) = Uuid::from_str(user_id) else {
+        return Err(ApiError::internal_server_error());
+    };
+
+    let mut codes = Vec::new();
+    let mut recovery_codes = Vec::new();
+    for _ in 0..RECOVERY_CODES_COUNT {
+        let code = recovery_code::new();
+        let code_hash = hash_recovery_code(&code, state);
+        recovery_codes.push(RecoveryCode::new(user_id, &code_hash));
+        codes.push(code);
+    }
+
+    let Ok(mut tx) = state.pool.begin().await else {
+        return Err(ApiError::internal_server_error());
+    };
+
+    let sqlx_result = sqlx::query("DELETE FROM recovery_codes WHERE user_id = $1")
+        .bind(user_uuid)
+        .execute(&mut *tx)
+        .await;
+    if let Err(e) = sqlx_result {
+        tracing::error!(%e);
+        return Err(ApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to create recovery codes.",
+        ));
+    }
+
+    for recovery_code in &recovery_codes {
+        let sqlx_result = sqlx::query(
+            "
+            INSERT INTO recovery_codes (id, user_id, code_hash, created_at)
+            VALUES ($1, $2, $3, $4)
+            ",
+        )
+        .bind(recovery_code.id)
+        .bind(recovery_code.user_id)
+        .bind(&recovery_code.code_hash)
+        .bind(recovery_code.created_at)
+        .execute(&mut *tx)
+        .await;
+
+        if let Err(e) = sqlx_result {
+            tracing::error!(%e);
+            return Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to create recovery codes.",
+            ));
+        }
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(codes),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to create recovery codes.",
+            ))
+        }
+    }
+}
+
+pub async fn consume_recovery_code(
+    user_id: &str,
+    code: &str,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let Ok(user_id) = Uuid::from_str(user_id) else {
+        return Err(ApiError::new(StatusCode::UNAUTHORIZED, "Invalid code."));
+    };
+
+    let sqlx_result = sqlx::query(
+        "
+        DELETE FROM recovery_codes
+        WHERE user_id = $1 AND code_hash = $2
+        ",
+    )
+    .bind(user_id)
+    .bind(hash_recovery_code(code, state))
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(ApiError::new(StatusCode::UNAUTHORIZED, "Invalid code.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to use recovery code.",
+            ))
+        }
+    }
+}
+
+pub async fn delete_recovery_codes(user_id: &str, state: &AppState) -> Result<(), ApiError> {
+    let Ok(user_id) = Uuid::from_str(user_id) else {
+        return Err(ApiError::internal_server_error());
+    };
+
+    let sqlx_result = sqlx::query("DELETE FROM recovery_codes WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&state.pool)
+        .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to delete recovery codes.",
+            ))
+        }
+    }
+}
+
+// codes are random enough that a keyed hash is as safe as a password hash, and far cheaper
+fn hash_recovery_code(code: &str, state: &AppState) -> String {
+    secret_token::hash(
+        &code.to_lowercase(),
+        &state.envy.refresh_token_secret,
+        SecretTokenDomain::RECOVERY_CODE,
+    )
+}
@@ -0,0 +1,35 @@
+use axum::{extract::State, Json};
+use validator::Validate;
+
+use crate::{
+    app::models::{api_error::ApiError, app_state::AppState},
+    auth::{enums::scope::Scope, models::access_token_claims::ExtractClaims},
+};
+
+use super::{
+    dtos::edit_settings_dto::EditSettingsDto, models::user_settings::UserSettings, service,
+};
+
+pub async fn get_settings(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<Json<UserSettings>, ApiError> {
+    claims.require_scope(Scope::USERS_READ)?;
+    match service::get_user_settings(&claims.sub, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn edit_settings(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+    Json(dto): Json<EditSettingsDto>,
+) -> Result<Json<UserSettings>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    dto.validate()?;
+    match service::edit_user_settings(&dto, &claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_quiet_hours", skip_on_field_errors = false))]
+pub struct EditSettingsDto {
+    #[validate(range(
+        min = 0,
+        max = 10080,
+        message = "reminder_lead_time must be between 0 and 10080 minutes."
+    ))]
+    pub reminder_lead_time: Option<i32>,
+    #[validate(range(
+        min = 0,
+        max = 1439,
+        message = "quiet_hours_start must be between 0 and 1439 minutes."
+    ))]
+    pub quiet_hours_start: Option<i16>,
+    #[validate(range(
+        min = 0,
+        max = 1439,
+        message = "quiet_hours_end must be between 0 and 1439 minutes."
+    ))]
+    pub quiet_hours_end: Option<i16>,
+    pub clear_quiet_hours: Option<bool>,
+    pub quiet_hours_bypass: Option<bool>,
+    #[validate(custom = "super::validate_timezone")]
+    pub timezone: Option<String>,
+    #[validate(custom = "super::validate_memo_priority")]
+    pub default_memo_priority: Option<i16>,
+    #[validate(range(min = 0, max = 1, message = "default_memo_visibility must be 0 or 1."))]
+    pub default_memo_visibility: Option<i16>,
+    #[validate(regex(path = "super::LOCALE_REGEX", message = "locale is invalid."))]
+    pub locale: Option<String>,
+    #[validate(range(min = 0, max = 6, message = "week_start must be between 0 and 6."))]
+    pub week_start: Option<i16>,
+    #[validate(custom = "super::validate_notification_channels")]
+    pub notification_channels: Option<Vec<String>>,
+    pub digest_enabled: Option<bool>,
+    #[validate(range(
+        min = 0,
+        max = 1439,
+        message = "digest_time must be between 0 and 1439 minutes."
+    ))]
+    pub digest_time: Option<i16>,
+}
+
+fn validate_quiet_hours(dto: &EditSettingsDto) -> Result<(), ValidationError> {
+    if dto.quiet_hours_start.is_some() != dto.quiet_hours_end.is_some() {
+        let mut error = ValidationError::new("invalid_quiet_hours");
+        error.message = Some("quiet_hours_start and quiet_hours_end must be set together.".into());
+        return Err(error);
+    }
+    if dto.clear_quiet_hours == Some(true) && dto.quiet_hours_start.is_some() {
+        let mut error = ValidationError::new("invalid_quiet_hours");
+        error.message = Some("quiet hours cannot be set and cleared at once.".into());
+        return Err(error);
+    }
+
+    Ok(())
+}
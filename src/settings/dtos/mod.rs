@@ -0,0 +1,55 @@
+use std::{borrow::Cow, str::FromStr};
+
+use chrono_tz::Tz;
+use regex::Regex;
+use validator::ValidationError;
+
+use crate::{
+    memos::enums::memo_priority::MemoPriority,
+    settings::enums::notification_channel::NotificationChannel,
+};
+
+pub mod edit_settings_dto;
+
+lazy_static! {
+    pub static ref LOCALE_REGEX: Regex = Regex::new(r"^[a-z]{2,3}(-[A-Z]{2})?$").unwrap();
+}
+
+pub fn validate_timezone(value: &str) -> Result<(), ValidationError> {
+    match Tz::from_str(value).is_ok() {
+        true => Ok(()),
+        false => {
+            let mut error = ValidationError::new("invalid_timezone");
+            error.message = Some(Cow::from("timezone must be a valid IANA timezone."));
+            Err(error)
+        }
+    }
+}
+
+pub fn validate_memo_priority(value: i16) -> Result<(), ValidationError> {
+    match (MemoPriority::LOW..=MemoPriority::HIGH).contains(&value) {
+        true => Ok(()),
+        false => {
+            let mut error = ValidationError::new("invalid_priority");
+            error.message = Some(Cow::from("default_memo_priority is invalid."));
+            Err(error)
+        }
+    }
+}
+
+pub fn validate_notification_channels(value: &[String]) -> Result<(), ValidationError> {
+    match value
+        .iter()
+        .all(|channel| NotificationChannel::ALL.contains(&channel.as_str()))
+    {
+        true => Ok(()),
+        false => {
+            let mut error = ValidationError::new("invalid_notification_channel");
+            error.message = Some(Cow::from(format!(
+                "notification_channels must only contain {}.",
+                NotificationChannel::ALL.join(", ")
+            )));
+            Err(error)
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod notification_channel;
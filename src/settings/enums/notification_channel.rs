@@ -0,0 +1,9 @@
+#[non_exhaustive]
+pub struct NotificationChannel;
+
+impl NotificationChannel {
+    pub const PUSH: &'static str = "push";
+    pub const EMAIL: &'static str = "email";
+
+    pub const ALL: [&'static str; 2] = [Self::PUSH, Self::EMAIL];
+}
@@ -0,0 +1,54 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+use crate::{
+    app, memos::enums::memo_priority::MemoPriority,
+    settings::enums::notification_channel::NotificationChannel,
+};
+
+// quiet hours are minutes after midnight in the user's timezone
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserSettings {
+    #[serde(skip_serializing)]
+    pub user_id: sqlx::types::Uuid,
+    pub reminder_lead_time: i32,
+    pub quiet_hours_start: Option<i16>,
+    pub quiet_hours_end: Option<i16>,
+    pub quiet_hours_bypass: bool,
+    pub timezone: String,
+    pub default_memo_priority: i16,
+    pub default_memo_visibility: i16,
+    pub locale: String,
+    pub week_start: i16,
+    pub notification_channels: Vec<String>,
+    pub digest_enabled: bool,
+    pub digest_time: i16,
+    pub updated_at: i64,
+    pub created_at: i64,
+}
+
+impl UserSettings {
+    pub fn new(user_id: &str) -> Self {
+        let current_time = app::util::time::current_time_in_millis();
+
+        Self {
+            user_id: sqlx::types::Uuid::from_str(user_id).unwrap(),
+            reminder_lead_time: 0,
+            quiet_hours_start: None,
+            quiet_hours_end: None,
+            quiet_hours_bypass: true,
+            timezone: "UTC".to_string(),
+            default_memo_priority: MemoPriority::NORMAL,
+            default_memo_visibility: 0,
+            locale: "en".to_string(),
+            week_start: 0,
+            notification_channels: vec![NotificationChannel::PUSH.to_string()],
+            digest_enabled: false,
+            digest_time: 480,
+            updated_at: current_time,
+            created_at: current_time,
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use std::str::FromStr;
+
+use axum::http::StatusCode;
+use sqlx::{types::Uuid, Postgres};
+
+use crate::{
+    app::{
+        models::{api_error::ApiError, app_state::AppState},
+        util::time,
+    },
+    auth::models::access_token_claims::AccessTokenClaims,
+};
+
+use super::{dtos::edit_settings_dto::EditSettingsDto, models::user_settings::UserSettings};
+
+// users without a row get the defaults, a row is only written on the first edit
+pub async fn get_user_settings(user_id: &str, state: &AppState) -> Result<UserSettings, ApiError> {
+    let sqlx_result =
+        sqlx::query_as::<Postgres, UserSettings>("SELECT * FROM user_settings WHERE user_id = $1")
+            .bind(Uuid::from_str(user_id).unwrap_or_default())
+            .fetch_optional(&state.pool)
+            .await;
+
+    match sqlx_result {
+        Ok(data) => match data {
+            Some(user_settings) => Ok(user_settings),
+            None => Ok(UserSettings::new(user_id)),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get settings.",
+            ))
+        }
+    }
+}
+
+pub async fn edit_user_settings(
+    dto: &EditSettingsDto,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<UserSettings, ApiError> {
+    let user_settings = UserSettings::new(&claims.sub);
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO user_settings (
+            user_id, reminder_lead_time, quiet_hours_start, quiet_hours_end,
+            quiet_hours_bypass, timezone, default_memo_priority, default_memo_visibility,
+            locale, week_start, notification_channels, digest_enabled, digest_time,
+            updated_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+        ON CONFLICT (user_id) DO NOTHING
+        ",
+    )
+    .bind(user_settings.user_id)
+    .bind(user_settings.reminder_lead_time)
+    .bind(user_settings.quiet_hours_start)
+    .bind(user_settings.quiet_hours_end)
+    .bind(user_settings.quiet_hours_bypass)
+    .bind(&user_settings.timezone)
+    .bind(user_settings.default_memo_priority)
+    .bind(user_settings.default_memo_visibility)
+    .bind(&user_settings.locale)
+    .bind(user_settings.week_start)
+    .bind(&user_settings.notification_channels)
+    .bind(user_settings.digest_enabled)
+    .bind(user_settings.digest_time)
+    .bind(user_settings.updated_at)
+    .bind(user_settings.created_at)
+    .execute(&state.pool)
+    .await;
+
+    if let Err(e) = sqlx_result {
+        tracing::error!(%e);
+        return Err(ApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to edit settings.",
+        ));
+    }
+
+    let clear_quiet_hours = dto.clear_quiet_hours.unwrap_or(false);
+
+    // SQL
+    let mut query = "UPDATE user_settings SET ".to_string();
+    let mut index: u8 = 0;
+
+    if dto.reminder_lead_time.is_some() {
+        index += 1;
+        query.push_str(&format!("reminder_lead_time = ${}, ", index));
+    }
+    if dto.quiet_hours_start.is_some() {
+        index += 1;
+        query.push_str(&format!("quiet_hours_start = ${}, ", index));
+    }
+    if dto.quiet_hours_end.is_some() {
+        index += 1;
+        query.push_str(&format!("quiet_hours_end = ${}, ", index));
+    }
+    if clear_quiet_hours {
+        query.push_str("quiet_hours_start = NULL, quiet_hours_end = NULL, ");
+    }
+    if dto.quiet_hours_bypass.is_some() {
+        index += 1;
+        query.push_str(&format!("quiet_hours_bypass = ${}, ", index));
+    }
+    if dto.timezone.is_some() {
+        index += 1;
+        query.push_str(&format!("timezone = ${}, ", index));
+    }
+    if dto.default_memo_priority.is_some() {
+        index += 1;
+        query.push_str(&format!("default_memo_priority = ${}, ", index));
+    }
+    if dto.default_memo_visibility.is_some() {
+        index += 1;
+        query.push_str(&format!("default_memo_visibility = ${}, ", index));
+    }
+    if dto.locale.is_some() {
+        index += 1;
+        query.push_str(&format!("locale = ${}, ", index));
+    }
+    if dto.week_start.is_some() {
+        index += 1;
+        query.push_str(&format!("week_start = ${}, ", index));
+    }
+    if dto.notification_channels.is_some() {
+        index += 1;
+        query.push_str(&format!("notification_channels = ${}, ", index));
+    }
+    if dto.digest_enabled.is_some() {
+        index += 1;
+        query.push_str(&format!("digest_enabled = ${}, ", index));
+    }
+    if dto.digest_time.is_some() {
+        index += 1;
+        query.push_str(&format!("digest_time = ${}, ", index));
+    }
+
+    index += 1;
+    query.push_str(&format!("updated_at = ${} ", index));
+    index += 1;
+    query.push_str(&format!("WHERE user_id = ${} ", index));
+    query.push_str("RETURNING *");
+
+    // SQLX
+    let mut sqlx = sqlx::query_as::<Postgres, UserSettings>(&query);
+
+    if let Some(reminder_lead_time) = &dto.reminder_lead_time {
+        sqlx = sqlx.bind(reminder_lead_time);
+    }
+    if let Some(quiet_hours_start) = &dto.quiet_hours_start {
+        sqlx = sqlx.bind(quiet_hours_start);
+    }
+    if let Some(quiet_hours_end) = &dto.quiet_hours_end {
+        sqlx = sqlx.bind(quiet_hours_end);
+    }
+    if let Some(quiet_hours_bypass) = &dto.quiet_hours_bypass {
+        sqlx = sqlx.bind(quiet_hours_bypass);
+    }
+    if let Some(timezone) = &dto.timezone {
+        sqlx = sqlx.bind(timezone);
+    }
+    if let Some(default_memo_priority) = &dto.default_memo_priority {
+        sqlx = sqlx.bind(default_memo_priority);
+    }
+    if let Some(default_memo_visibility) = &dto.default_memo_visibility {
+        sqlx = sqlx.bind(default_memo_visibility);
+    }
+    if let Some(locale) = &dto.locale {
+        sqlx = sqlx.bind(locale);
+    }
+    if let Some(week_start) = &dto.week_start {
+        sqlx = sqlx.bind(week_start);
+    }
+    if let Some(notification_channels) = &dto.notification_channels {
+        let mut notification_channels = notification_channels.clone();
+        notification_channels.sort();
+        notification_channels.dedup();
+        sqlx = sqlx.bind(notification_channels);
+    }
+    if let Some(digest_enabled) = &dto.digest_enabled {
+        sqlx = sqlx.bind(digest_enabled);
+    }
+    if let Some(digest_time) = &dto.digest_time {
+        sqlx = sqlx.bind(digest_time);
+    }
+    sqlx = sqlx.bind(time::current_time_in_millis());
+    sqlx = sqlx.bind(Uuid::from_str(&claims.sub).unwrap_or_default());
+
+    let sqlx_result = sqlx.fetch_optional(&state.pool).await;
+
+    match sqlx_result {
+        Ok(data) => match data {
+            Some(user_settings) => Ok(user_settings),
+            None => Err(ApiError::new(StatusCode::NOT_FOUND, "Settings not found.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to edit settings.",
+            ))
+        }
+    }
+}
+
+// users whose local digest time has passed and who have not had today's digest yet
+pub async fn get_due_digest_user_settings(
+    limit: i64,
+    state: &AppState,
+) -> Result<Vec<UserSettings>, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, UserSettings>(
+        "
+        SELECT * FROM user_settings
+        WHERE digest_enabled = true
+        AND EXTRACT(HOUR FROM now() AT TIME ZONE timezone) * 60
+            + EXTRACT(MINUTE FROM now() AT TIME ZONE timezone) >= digest_time
+        AND (
+            digest_sent_at IS NULL
+            OR digest_sent_at < EXTRACT(
+                EPOCH FROM date_trunc('day', now() AT TIME ZONE timezone) AT TIME ZONE timezone
+            ) * 1000
+        )
+        AND (digest_leased_until IS NULL OR digest_leased_until < $2)
+        ORDER BY digest_sent_at ASC NULLS FIRST
+        LIMIT $1
+        ",
+    )
+    .bind(limit)
+    .bind(time::current_time_in_millis())
+    .fetch_all(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(user_settings) => Ok(user_settings),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get settings.",
+            ))
+        }
+    }
+}
+
+// returns false when another instance already sent today's digest or is sending it,
+// the lease runs out on its own if that instance dies before mark_digest_sent
+pub async fn claim_digest(
+    user_id: &Uuid,
+    start_of_day: i64,
+    leased_until: i64,
+    state: &AppState,
+) -> Result<bool, ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE user_settings SET digest_leased_until = $1
+        WHERE user_id = $2 AND digest_enabled = true
+        AND (digest_sent_at IS NULL OR digest_sent_at < $3)
+        AND (digest_leased_until IS NULL OR digest_leased_until < $4)
+        ",
+    )
+    .bind(leased_until)
+    .bind(user_id)
+    .bind(start_of_day)
+    .bind(time::current_time_in_millis())
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => Ok(result.rows_affected() > 0),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to edit settings.",
+            ))
+        }
+    }
+}
+
+pub async fn mark_digest_sent(user_id: &Uuid, state: &AppState) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE user_settings SET digest_sent_at = $1, digest_leased_until = NULL
+        WHERE user_id = $2
+        ",
+    )
+    .bind(time::current_time_in_millis())
+    .bind(user_id)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to edit settings.",
+            ))
+        }
+    }
+}
+
+pub async fn disable_digest(user_id: &str, state: &AppState) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE user_settings SET digest_enabled = false, updated_at = $1
+        WHERE user_id = $2
+        ",
+    )
+    .bind(time::current_time_in_millis())
+    .bind(Uuid::from_str(user_id).unwrap_or_default())
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to unsubscribe.",
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    use crate::app::test_util;
+
+    use super::*;
+
+    #[sqlx::test(migrations = false)]
+    async fn edits_and_clears_quiet_hours(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let claims = test_util::access_claims(&test_util::insert_user("someone", &state).await);
+
+        let dto: EditSettingsDto = serde_json::from_value(json!({
+            "reminder_lead_time": 30,
+            "quiet_hours_start": 1320,
+            "quiet_hours_end": 420,
+        }))
+        .unwrap();
+        let user_settings = edit_user_settings(&dto, &claims, &state).await.unwrap();
+        assert_eq!(user_settings.reminder_lead_time, 30);
+        assert_eq!(user_settings.quiet_hours_start, Some(1320));
+        assert_eq!(user_settings.quiet_hours_end, Some(420));
+
+        let dto: EditSettingsDto =
+            serde_json::from_value(json!({ "clear_quiet_hours": true })).unwrap();
+        let user_settings = edit_user_settings(&dto, &claims, &state).await.unwrap();
+        assert_eq!(user_settings.reminder_lead_time, 30);
+        assert_eq!(user_settings.quiet_hours_start, None);
+        assert_eq!(user_settings.quiet_hours_end, None);
+
+        let user_settings = get_user_settings(&claims.sub, &state).await.unwrap();
+        assert_eq!(user_settings.reminder_lead_time, 30);
+        assert_eq!(user_settings.quiet_hours_start, None);
+    }
+}
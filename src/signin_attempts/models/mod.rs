@@ -0,0 +1 @@
+pub mod signin_attempt;
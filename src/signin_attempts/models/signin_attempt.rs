@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SigninAttempt {
+    pub key: String,
+    pub failures: i32,
+    pub locked_until: i64,
+    pub last_failed_at: i64,
+    pub alerted_at: Option<i64>,
+}
@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+use tokio::{task, time::interval};
+
+use crate::app::models::app_state::AppState;
+
+use super::service;
+
+pub fn spawn(state: AppState) {
+    task::spawn(async move {
+        let mut interval = interval(Duration::from_secs(3600));
+
+        loop {
+            interval.tick().await;
+
+            let state = state.clone();
+            task::spawn(async move {
+                let _ = service::delete_expired_signin_attempts(&state).await;
+            });
+        }
+    });
+}
@@ -0,0 +1,254 @@
+use axum::http::StatusCode;
+use sqlx::Postgres;
+
+use crate::{
+    app::{
+        models::{api_error::ApiError, app_state::AppState},
+        util::time,
+    },
+    auth::config::{SIGNIN_ALERT_THRESHOLD, SIGNIN_ATTEMPTS_RESET, SIGNIN_MAX_LOCKOUT},
+};
+
+use super::models::signin_attempt::SigninAttempt;
+
+pub fn account_key(identifier: &str) -> String {
+    format!("account:{}", identifier.to_lowercase())
+}
+
+pub fn ip_key(ip: &str) -> String {
+    format!("ip:{}", ip)
+}
+
+pub async fn check_signin_attempts(keys: &[String], state: &AppState) -> Result<(), ApiError> {
+    let current_time = time::current_time_in_millis();
+
+    let sqlx_result = sqlx::query_scalar::<Postgres, i64>(
+        "
+        SELECT COALESCE(MAX(locked_until), 0) FROM signin_attempts
+        WHERE key = ANY($1)
+        ",
+    )
+    .bind(keys)
+    .fetch_one(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(locked_until) => match locked_until > current_time {
+            true => Err(ApiError::too_many_requests(
+                "Too many failed sign-in attempts. Try again later.",
+                ((locked_until - current_time) as u64).div_ceil(1000),
+            )),
+            false => Ok(()),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get sign-in attempts.",
+            ))
+        }
+    }
+}
+
+// counts the attempt before the password is verified so concurrent guesses
+// cannot all slip past check_signin_attempts, a locked key is left untouched
+pub async fn reserve_signin_attempt(
+    key: &str,
+    free_attempts: i32,
+    state: &AppState,
+) -> Result<SigninAttempt, ApiError> {
+    let current_time = time::current_time_in_millis();
+
+    // lockout doubles with every failure past the free attempts
+    let sqlx_result = sqlx::query_as::<Postgres, SigninAttempt>(
+        "
+        INSERT INTO signin_attempts (key, failures, locked_until, last_failed_at)
+        VALUES ($1, 1, 0, $2)
+        ON CONFLICT (key) DO UPDATE SET
+            failures = CASE
+                WHEN signin_attempts.last_failed_at < $3 THEN 1
+                ELSE signin_attempts.failures + 1
+            END,
+            locked_until = CASE
+                WHEN signin_attempts.last_failed_at < $3 OR signin_attempts.failures + 1 <= $4 THEN 0
+                ELSE $2 + LEAST(1::BIGINT << LEAST(signin_attempts.failures - $4, 31), $5) * 1000
+            END,
+            last_failed_at = $2
+        WHERE signin_attempts.locked_until <= $2
+        RETURNING *
+        ",
+    )
+    .bind(key)
+    .bind(current_time)
+    .bind(current_time - (SIGNIN_ATTEMPTS_RESET * 1000) as i64)
+    .bind(free_attempts)
+    .bind(SIGNIN_MAX_LOCKOUT as i64)
+    .fetch_optional(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(Some(signin_attempt)) => Ok(signin_attempt),
+        Ok(None) => {
+            check_signin_attempts(&[key.to_string()], state).await?;
+            Err(ApiError::too_many_requests(
+                "Too many failed sign-in attempts. Try again later.",
+                1,
+            ))
+        }
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to record sign-in attempt.",
+            ))
+        }
+    }
+}
+
+// gives back an attempt reserved by a sign-in that turned out to succeed
+pub async fn release_signin_attempt(
+    key: &str,
+    free_attempts: i32,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE signin_attempts SET
+            failures = GREATEST(failures - 1, 0),
+            locked_until = CASE WHEN failures - 1 <= $2 THEN 0 ELSE locked_until END
+        WHERE key = $1
+        ",
+    )
+    .bind(key)
+    .bind(free_attempts)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to record sign-in attempt.",
+            ))
+        }
+    }
+}
+
+pub async fn mark_signin_alerted(key: &str, state: &AppState) -> Result<bool, ApiError> {
+    let current_time = time::current_time_in_millis();
+
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE signin_attempts SET alerted_at = $1
+        WHERE key = $2 AND failures >= $3 AND (alerted_at IS NULL OR alerted_at < $4)
+        ",
+    )
+    .bind(current_time)
+    .bind(key)
+    .bind(SIGNIN_ALERT_THRESHOLD)
+    .bind(current_time - (SIGNIN_ATTEMPTS_RESET * 1000) as i64)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => Ok(result.rows_affected() > 0),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to record sign-in attempt.",
+            ))
+        }
+    }
+}
+
+pub async fn clear_signin_attempts(key: &str, state: &AppState) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query("DELETE FROM signin_attempts WHERE key = $1")
+        .bind(key)
+        .execute(&state.pool)
+        .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to clear sign-in attempts.",
+            ))
+        }
+    }
+}
+
+pub async fn delete_expired_signin_attempts(state: &AppState) -> Result<(), ApiError> {
+    let current_time = time::current_time_in_millis();
+
+    let sqlx_result = sqlx::query(
+        "
+        DELETE FROM signin_attempts
+        WHERE last_failed_at < $1 AND locked_until < $2
+        ",
+    )
+    .bind(current_time - (SIGNIN_ATTEMPTS_RESET * 1000) as i64)
+    .bind(current_time)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to delete sign-in attempts.",
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use sqlx::PgPool;
+
+    use crate::app::test_util;
+
+    use super::*;
+
+    #[sqlx::test(migrations = false)]
+    async fn reserve_locks_concurrent_attempts_past_free_attempts(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let key = account_key("someone");
+
+        let attempts =
+            futures::future::join_all((0..10).map(|_| reserve_signin_attempt(&key, 3, &state)))
+                .await;
+
+        let reserved = attempts.iter().filter(|attempt| attempt.is_ok()).count();
+        assert_eq!(reserved, 4);
+        assert!(attempts
+            .iter()
+            .filter_map(|attempt| attempt.as_ref().err())
+            .all(|e| e.code == StatusCode::TOO_MANY_REQUESTS));
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn release_unlocks_below_free_attempts(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let key = ip_key("127.0.0.1");
+
+        for _ in 0..3 {
+            reserve_signin_attempt(&key, 2, &state).await.unwrap();
+        }
+        assert!(check_signin_attempts(std::slice::from_ref(&key), &state)
+            .await
+            .is_err());
+
+        release_signin_attempt(&key, 2, &state).await.unwrap();
+        assert!(check_signin_attempts(std::slice::from_ref(&key), &state)
+            .await
+            .is_ok());
+    }
+}
@@ -0,0 +1,27 @@
+pub static USERNAME_EDIT_COOLDOWN: u64 = 2592000;
+pub static RESERVED_USERNAMES: [&str; 24] = [
+    "about",
+    "account",
+    "admin",
+    "administrator",
+    "api",
+    "app",
+    "auth",
+    "help",
+    "me",
+    "memos",
+    "moderator",
+    "null",
+    "perroquet",
+    "privacy",
+    "root",
+    "settings",
+    "signin",
+    "signup",
+    "staff",
+    "support",
+    "system",
+    "terms",
+    "undefined",
+    "users",
+];
@@ -6,16 +6,24 @@ use validator::Validate;
 
 use crate::{
     app::models::{api_error::ApiError, app_state::AppState},
-    auth::models::access_token_claims::ExtractClaims,
+    auth::{enums::scope::Scope, models::access_token_claims::ExtractClaims},
 };
 
-use super::{dtos::get_users_filter_dto::GetUsersFilterDto, models::user::User, service};
+use super::{
+    dtos::{
+        edit_user_dto::EditUserDto, get_username_availability_dto::GetUsernameAvailabilityDto,
+        get_users_filter_dto::GetUsersFilterDto,
+    },
+    models::{user::User, username_availability::UsernameAvailability},
+    service,
+};
 
 pub async fn get_users(
     State(state): State<AppState>,
     ExtractClaims(claims): ExtractClaims,
     Query(dto): Query<GetUsersFilterDto>,
 ) -> Result<Json<Vec<User>>, ApiError> {
+    claims.require_scope(Scope::USERS_READ)?;
     dto.validate()?;
     match service::get_users(&dto, &claims, &state).await {
         Ok(data) => Ok(Json(data)),
@@ -27,7 +35,32 @@ pub async fn get_me(
     State(state): State<AppState>,
     ExtractClaims(claims): ExtractClaims,
 ) -> Result<Json<User>, ApiError> {
-    match service::get_user_by_id(&claims.id, &state).await {
+    claims.require_scope(Scope::USERS_READ)?;
+    match service::get_user_by_id(&claims.sub, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn edit_me(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+    Json(dto): Json<EditUserDto>,
+) -> Result<Json<User>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    dto.validate()?;
+    match service::edit_user(&dto, &claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_username_availability(
+    State(state): State<AppState>,
+    Query(dto): Query<GetUsernameAvailabilityDto>,
+) -> Result<Json<UsernameAvailability>, ApiError> {
+    dto.validate()?;
+    match service::get_username_availability(&dto, &state).await {
         Ok(data) => Ok(Json(data)),
         Err(e) => Err(e),
     }
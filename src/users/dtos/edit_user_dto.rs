@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct EditUserDto {
+    #[validate(length(
+        min = 3,
+        max = 24,
+        message = "username must be between 3 and 24 characters."
+    ))]
+    #[validate(regex(path = "crate::auth::dtos::USERNAME_REGEX"))]
+    pub username: Option<String>,
+    #[validate(length(
+        min = 1,
+        max = 32,
+        message = "displayname must be between 1 and 32 characters."
+    ))]
+    pub displayname: Option<String>,
+    pub private: Option<bool>,
+    pub discoverable_by_username: Option<bool>,
+    pub discoverable_by_email: Option<bool>,
+}
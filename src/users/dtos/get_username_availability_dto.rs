@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct GetUsernameAvailabilityDto {
+    #[validate(length(
+        min = 3,
+        max = 24,
+        message = "username must be between 3 and 24 characters."
+    ))]
+    #[validate(regex(path = "crate::auth::dtos::USERNAME_REGEX"))]
+    pub username: String,
+}
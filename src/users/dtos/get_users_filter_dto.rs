@@ -4,6 +4,11 @@ use validator::Validate;
 #[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct GetUsersFilterDto {
     pub id: Option<String>,
+    #[validate(length(
+        min = 1,
+        max = 64,
+        message = "search must be between 1 and 64 characters."
+    ))]
     pub search: Option<String>,
     pub sort: Option<String>,
     pub cursor: Option<String>,
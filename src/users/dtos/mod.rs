@@ -1 +1,3 @@
+pub mod edit_user_dto;
+pub mod get_username_availability_dto;
 pub mod get_users_filter_dto;
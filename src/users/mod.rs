@@ -1,3 +1,4 @@
+pub mod config;
 pub mod controller;
 pub mod dtos;
 pub mod models;
@@ -1 +1,2 @@
 pub mod user;
+pub mod username_availability;
@@ -7,8 +7,6 @@ use crate::{app::util::time, users::util};
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
     pub id: sqlx::types::Uuid,
-    #[serde(skip_serializing)]
-    pub id_apple: Option<String>,
     pub username: String,
     #[serde(skip_serializing)]
     pub username_key: String,
@@ -18,33 +16,45 @@ pub struct User {
     pub email_key: String,
     #[serde(skip_serializing)]
     pub password: Option<String>,
+    #[serde(skip_serializing)]
+    pub totp_secret: Option<String>,
+    #[serde(skip_serializing)]
+    pub totp_enabled: bool,
     pub displayname: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub avatar_url: Option<String>,
+    #[serde(skip_serializing)]
+    pub username_updated_at: Option<i64>,
+    #[serde(skip_serializing)]
+    pub private: bool,
+    #[serde(skip_serializing)]
+    pub discoverable_by_username: bool,
+    #[serde(skip_serializing)]
+    pub discoverable_by_email: bool,
     pub updated_at: i64,
     pub created_at: i64,
 }
 
 impl User {
-    pub fn new(
-        username: &Option<String>,
-        email: &str,
-        password_hash: &Option<String>,
-        id_apple: &Option<String>,
-    ) -> Self {
+    pub fn new(username: &Option<String>, email: &str, password_hash: &Option<String>) -> Self {
         let current_time = time::current_time_in_millis();
         let username = username.clone().unwrap_or(util::username::new());
 
         Self {
             id: Uuid::new_v4(),
-            id_apple: id_apple.clone(),
             username: username.to_string(),
             username_key: username.to_lowercase(),
             email: email.to_string(),
             email_key: email.to_lowercase(),
             password: password_hash.clone(),
+            totp_secret: None,
+            totp_enabled: false,
             displayname: username,
             avatar_url: None,
+            username_updated_at: None,
+            private: false,
+            discoverable_by_username: true,
+            discoverable_by_email: false,
             updated_at: current_time,
             created_at: current_time,
         }
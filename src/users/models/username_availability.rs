@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsernameAvailability {
+    pub username: String,
+    pub available: bool,
+}
@@ -1,28 +1,38 @@
+use std::str::FromStr;
+
 use axum::http::StatusCode;
-use sqlx::Postgres;
+use sqlx::{types::Uuid, Postgres};
 
 use crate::{
-    app::{self, models::api_error::ApiError},
+    app::{self, models::api_error::ApiError, util::time},
     auth::{
         dtos::signin_dto::SigninDto, models::access_token_claims::AccessTokenClaims, util::password,
     },
     AppState,
 };
 
-use super::{dtos::get_users_filter_dto::GetUsersFilterDto, models::user::User};
+use super::{
+    config::USERNAME_EDIT_COOLDOWN,
+    dtos::{
+        edit_user_dto::EditUserDto, get_username_availability_dto::GetUsernameAvailabilityDto,
+        get_users_filter_dto::GetUsersFilterDto,
+    },
+    models::{user::User, username_availability::UsernameAvailability},
+    util,
+};
 
 pub async fn create_user(user: User, state: &AppState) -> Result<User, ApiError> {
     let sqlx_result = sqlx::query(
         "
         INSERT INTO users (
-            id, id_apple, username, username_key, email, email_key,
-            password, displayname, avatar_url, updated_at, created_at
+            id, username, username_key, email, email_key,
+            password, displayname, avatar_url, private,
+            discoverable_by_username, discoverable_by_email, updated_at, created_at
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
         ",
     )
-    .bind(&user.id)
-    .bind(&user.id_apple)
+    .bind(user.id)
     .bind(&user.username)
     .bind(&user.username_key)
     .bind(&user.email)
@@ -30,8 +40,11 @@ pub async fn create_user(user: User, state: &AppState) -> Result<User, ApiError>
     .bind(&user.password)
     .bind(&user.displayname)
     .bind(&user.avatar_url)
-    .bind(&user.updated_at)
-    .bind(&user.created_at)
+    .bind(user.private)
+    .bind(user.discoverable_by_username)
+    .bind(user.discoverable_by_email)
+    .bind(user.updated_at)
+    .bind(user.created_at)
     .execute(&state.pool)
     .await;
 
@@ -48,10 +61,9 @@ pub async fn create_user(user: User, state: &AppState) -> Result<User, ApiError>
             };
 
             match code.as_str() {
-                app::util::sqlx::SqlStateCodes::UNIQUE_VIOLATION => Err(ApiError {
-                    code: StatusCode::CONFLICT,
-                    message: "User already exists.".to_string(),
-                }),
+                app::util::sqlx::SqlStateCodes::UNIQUE_VIOLATION => {
+                    Err(ApiError::new(StatusCode::CONFLICT, "User already exists."))
+                }
                 _ => {
                     tracing::error!(%e);
                     Err(ApiError::internal_server_error())
@@ -66,19 +78,47 @@ pub async fn get_users(
     claims: &AccessTokenClaims,
     state: &AppState,
 ) -> Result<Vec<User>, ApiError> {
+    let search = dto
+        .search
+        .as_ref()
+        .map(|search| search.trim().to_lowercase());
+    let id = match dto.id.as_deref().map(Uuid::from_str) {
+        Some(Ok(id)) => Some(id),
+        Some(Err(_)) => return Ok(Vec::new()),
+        None => None,
+    };
+
     // SQL
     let mut query = "SELECT * FROM users WHERE true".to_string();
-    let mut index: u8 = 0;
+    let mut index: u8 = 1;
+
+    // private profiles and blocks in either direction hide users from everyone but themselves
+    query.push_str(
+        " AND (users.id = $1 OR (users.private = false AND NOT EXISTS (
+            SELECT 1 FROM blocks
+            WHERE (blocks.user_id = users.id AND blocks.blocked_user_id = $1)
+            OR (blocks.user_id = $1 AND blocks.blocked_user_id = users.id)
+        )))",
+    );
 
-    if dto.id.is_some() {
+    if id.is_some() {
         index += 1;
-        query.push_str(&format!(" AND id = ${}", index))
+        query.push_str(&format!(" AND users.id = ${}", index))
     }
-    if dto.search.is_some() {
-        index += 1;
+    let search_index = index + 1;
+    if search.is_some() {
         query.push_str(&format!(
-            " AND title LIKE ${} OR content LIKE ${}",
-            index, index
+            " AND (
+                (users.discoverable_by_username AND (
+                    users.username_key LIKE ${prefix}
+                    OR lower(users.displayname) LIKE ${prefix}
+                    OR users.username_key % ${search}
+                    OR lower(users.displayname) % ${search}
+                ))
+                OR (users.discoverable_by_email AND users.email_key = ${search})
+            )",
+            search = search_index,
+            prefix = search_index + 1,
         ))
     }
 
@@ -88,7 +128,10 @@ pub async fn get_users(
     let limit = dto.limit.unwrap_or(100);
 
     if let Some(sort) = &dto.sort {
-        match app::util::dto::get_sort_params(sort, None) {
+        match app::util::dto::get_sort_params(
+            sort,
+            Some(vec!["created_at", "updated_at", "username_key"]),
+        ) {
             Ok(sort_params) => {
                 sort_field = sort_params.field;
                 sort_order = sort_params.order;
@@ -105,17 +148,28 @@ pub async fn get_users(
             }
         }
     }
-    query.push_str(&format!(" ORDER BY users.{} {}", sort_field, sort_order));
+    match (&search, &dto.sort) {
+        (Some(_), None) => query.push_str(&format!(
+            " ORDER BY GREATEST(
+                similarity(users.username_key, ${search}),
+                similarity(lower(users.displayname), ${search})
+            ) DESC, users.created_at DESC",
+            search = search_index,
+        )),
+        _ => query.push_str(&format!(" ORDER BY users.{} {}", sort_field, sort_order)),
+    }
     query.push_str(&format!(" LIMIT {}", limit));
 
     // SQLX
     let mut sqlx = sqlx::query_as::<Postgres, User>(&query);
 
-    if let Some(id) = &dto.id {
+    sqlx = sqlx.bind(Uuid::from_str(&claims.sub).unwrap_or_default());
+    if let Some(id) = id {
         sqlx = sqlx.bind(id);
     }
-    if let Some(search) = &dto.search {
+    if let Some(search) = &search {
         sqlx = sqlx.bind(search);
+        sqlx = sqlx.bind(format!("{}%", escape_like(search)));
     }
 
     let sqlx_result = sqlx.fetch_all(&state.pool).await;
@@ -132,30 +186,16 @@ pub async fn get_users(
     }
 }
 
-pub async fn get_user_by_id(id: &str, state: &AppState) -> Result<User, ApiError> {
-    let sqlx_result = sqlx::query_as::<Postgres, User>("SELECT * FROM users WHERE id = $1")
-        .bind(id)
-        .fetch_optional(&state.pool)
-        .await;
-
-    match sqlx_result {
-        Ok(data) => match data {
-            Some(user) => Ok(user),
-            None => Err(ApiError::new(StatusCode::NOT_FOUND, "User not found.")),
-        },
-        Err(e) => {
-            tracing::error!(%e);
-            Err(ApiError::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to get user.",
-            ))
-        }
-    }
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
 
-pub async fn get_user_by_id_apple(id_apple: &str, state: &AppState) -> Result<User, ApiError> {
-    let sqlx_result = sqlx::query_as::<Postgres, User>("SELECT * FROM users WHERE id_apple = $1")
-        .bind(id_apple)
+pub async fn get_user_by_id(id: &str, state: &AppState) -> Result<User, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, User>("SELECT * FROM users WHERE id = $1")
+        .bind(Uuid::from_str(id).unwrap_or_default())
         .fetch_optional(&state.pool)
         .await;
 
@@ -231,6 +271,209 @@ pub async fn get_user_by_email(email: &str, state: &AppState) -> Result<User, Ap
     }
 }
 
+pub async fn get_username_availability(
+    dto: &GetUsernameAvailabilityDto,
+    state: &AppState,
+) -> Result<UsernameAvailability, ApiError> {
+    if util::username::is_reserved(&dto.username) {
+        return Ok(UsernameAvailability {
+            username: dto.username.to_string(),
+            available: false,
+        });
+    }
+
+    let available = match get_user_by_username(&dto.username, state).await {
+        Ok(_) => false,
+        Err(e) => match e.code {
+            StatusCode::NOT_FOUND => true,
+            _ => return Err(e),
+        },
+    };
+
+    Ok(UsernameAvailability {
+        username: dto.username.to_string(),
+        available,
+    })
+}
+
+pub async fn edit_user(
+    dto: &EditUserDto,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<User, ApiError> {
+    let user = get_user_by_id(&claims.sub, state).await?;
+    if dto.username.is_none()
+        && dto.displayname.is_none()
+        && dto.private.is_none()
+        && dto.discoverable_by_username.is_none()
+        && dto.discoverable_by_email.is_none()
+    {
+        return Ok(user);
+    }
+
+    let current_time = time::current_time_in_millis();
+    let cooldown = (USERNAME_EDIT_COOLDOWN * 1000) as i64;
+
+    // changing only the casing keeps the same username_key and skips the cooldown
+    let username_key = match &dto.username {
+        Some(username) if username.to_lowercase() != user.username_key => {
+            if util::username::is_reserved(username) {
+                return Err(ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    "Username is reserved.",
+                ));
+            }
+            if let Some(username_updated_at) = user.username_updated_at {
+                let available_at = username_updated_at + cooldown;
+                if available_at > current_time {
+                    let retry_after = ((available_at - current_time) as u64).div_ceil(1000);
+                    return Err(ApiError::too_many_requests(
+                        "Username was changed recently.",
+                        retry_after,
+                    ));
+                }
+            }
+            Some(username.to_lowercase())
+        }
+        _ => None,
+    };
+
+    // SQL
+    let mut query = "UPDATE users SET ".to_string();
+    let mut index: u8 = 0;
+
+    if dto.username.is_some() {
+        index += 1;
+        query.push_str(&format!("username = ${}, ", index));
+    }
+    if username_key.is_some() {
+        index += 1;
+        query.push_str(&format!("username_key = ${}, ", index));
+        index += 1;
+        query.push_str(&format!("username_updated_at = ${}, ", index));
+    }
+    if dto.displayname.is_some() {
+        index += 1;
+        query.push_str(&format!("displayname = ${}, ", index));
+    }
+    if dto.private.is_some() {
+        index += 1;
+        query.push_str(&format!("private = ${}, ", index));
+    }
+    if dto.discoverable_by_username.is_some() {
+        index += 1;
+        query.push_str(&format!("discoverable_by_username = ${}, ", index));
+    }
+    if dto.discoverable_by_email.is_some() {
+        index += 1;
+        query.push_str(&format!("discoverable_by_email = ${}, ", index));
+    }
+
+    index += 1;
+    query.push_str(&format!("updated_at = ${} ", index));
+    index += 1;
+    query.push_str(&format!("WHERE id = ${} ", index));
+    if username_key.is_some() {
+        index += 1;
+        query.push_str(&format!(
+            "AND (username_updated_at IS NULL OR username_updated_at <= ${}) ",
+            index
+        ));
+    }
+    query.push_str("RETURNING *");
+
+    // SQLX
+    let mut sqlx = sqlx::query_as::<Postgres, User>(&query);
+
+    if let Some(username) = &dto.username {
+        sqlx = sqlx.bind(username);
+    }
+    if let Some(username_key) = &username_key {
+        sqlx = sqlx.bind(username_key);
+        sqlx = sqlx.bind(current_time);
+    }
+    if let Some(displayname) = &dto.displayname {
+        sqlx = sqlx.bind(displayname);
+    }
+    if let Some(private) = &dto.private {
+        sqlx = sqlx.bind(private);
+    }
+    if let Some(discoverable_by_username) = &dto.discoverable_by_username {
+        sqlx = sqlx.bind(discoverable_by_username);
+    }
+    if let Some(discoverable_by_email) = &dto.discoverable_by_email {
+        sqlx = sqlx.bind(discoverable_by_email);
+    }
+    sqlx = sqlx.bind(current_time);
+    sqlx = sqlx.bind(user.id);
+    if username_key.is_some() {
+        sqlx = sqlx.bind(current_time - cooldown);
+    }
+
+    let sqlx_result = sqlx.fetch_optional(&state.pool).await;
+
+    match sqlx_result {
+        Ok(data) => match data {
+            Some(user) => Ok(user),
+            None => Err(ApiError::new(StatusCode::NOT_FOUND, "User not found.")),
+        },
+        Err(e) => {
+            let Some(db_err) = e.as_database_error() else {
+                tracing::error!(%e);
+                return Err(ApiError::internal_server_error());
+            };
+            let Some(code) = app::util::sqlx::extract_db_err_code(db_err) else {
+                tracing::error!(%e);
+                return Err(ApiError::internal_server_error());
+            };
+
+            match code.as_str() {
+                app::util::sqlx::SqlStateCodes::UNIQUE_VIOLATION => Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    "Username already exists.",
+                )),
+                _ => {
+                    tracing::error!(%e);
+                    Err(ApiError::internal_server_error())
+                }
+            }
+        }
+    }
+}
+
+pub async fn edit_user_avatar_url(
+    id: &str,
+    avatar_url: Option<&str>,
+    state: &AppState,
+) -> Result<User, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, User>(
+        "
+        UPDATE users SET avatar_url = $1, updated_at = $2
+        WHERE id = $3
+        RETURNING *
+        ",
+    )
+    .bind(avatar_url)
+    .bind(time::current_time_in_millis())
+    .bind(Uuid::from_str(id).unwrap_or_default())
+    .fetch_optional(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(data) => match data {
+            Some(user) => Ok(user),
+            None => Err(ApiError::new(StatusCode::NOT_FOUND, "User not found.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to edit avatar.",
+            ))
+        }
+    }
+}
+
 pub async fn edit_user_email_pending(
     id: &str,
     email_pending: &str,
@@ -243,7 +486,7 @@ pub async fn edit_user_email_pending(
         ",
     )
     .bind(email_pending)
-    .bind(id)
+    .bind(Uuid::from_str(id).unwrap_or_default())
     .execute(&state.pool)
     .await;
 
@@ -267,7 +510,7 @@ pub async fn approve_user_email_pending(id: &str, state: &AppState) -> Result<()
         WHERE id = $1 AND email_pending IS NOT NULL
         ",
     )
-    .bind(id)
+    .bind(Uuid::from_str(id).unwrap_or_default())
     .execute(&state.pool)
     .await;
 
@@ -291,7 +534,12 @@ pub async fn edit_user_password(
     new_password: &str,
     state: &AppState,
 ) -> Result<(), ApiError> {
-    let Ok(password_hash) = password::hash(new_password.to_string()).await else {
+    let Ok(password_hash) = password::hash(
+        new_password.to_string(),
+        state.authman.argon2_params().clone(),
+    )
+    .await
+    else {
         return Err(ApiError::internal_server_error());
     };
 
@@ -302,7 +550,7 @@ pub async fn edit_user_password(
         ",
     )
     .bind(&password_hash)
-    .bind(id)
+    .bind(Uuid::from_str(id).unwrap_or_default())
     .execute(&state.pool)
     .await;
 
@@ -317,3 +565,266 @@ pub async fn edit_user_password(
         }
     }
 }
+
+// only replaces the hash it was derived from, so a concurrent password change wins
+pub async fn rehash_user_password(
+    id: &str,
+    old_password_hash: &str,
+    new_password_hash: &str,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE users SET password = $1
+        WHERE id = $2 AND password = $3
+        ",
+    )
+    .bind(new_password_hash)
+    .bind(Uuid::from_str(id).unwrap_or_default())
+    .bind(old_password_hash)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::internal_server_error())
+        }
+    }
+}
+
+pub async fn edit_user_totp_secret(
+    id: &str,
+    totp_secret: &str,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE users SET totp_secret = $1
+        WHERE id = $2 AND totp_enabled = false
+        ",
+    )
+    .bind(totp_secret)
+    .bind(Uuid::from_str(id).unwrap_or_default())
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(ApiError::new(
+                StatusCode::CONFLICT,
+                "Two-factor authentication is already enabled.",
+            )),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::internal_server_error())
+        }
+    }
+}
+
+pub async fn enable_user_totp(id: &str, step: i64, state: &AppState) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE users SET totp_enabled = true, totp_last_used_step = $1
+        WHERE id = $2 AND totp_secret IS NOT NULL AND totp_enabled = false
+        ",
+    )
+    .bind(step)
+    .bind(Uuid::from_str(id).unwrap_or_default())
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(ApiError::new(
+                StatusCode::CONFLICT,
+                "Two-factor authentication is already enabled.",
+            )),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::internal_server_error())
+        }
+    }
+}
+
+pub async fn disable_user_totp(id: &str, state: &AppState) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE users
+        SET totp_secret = NULL, totp_enabled = false, totp_last_used_step = NULL
+        WHERE id = $1
+        ",
+    )
+    .bind(Uuid::from_str(id).unwrap_or_default())
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(ApiError::new(StatusCode::NOT_FOUND, "User not found.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::internal_server_error())
+        }
+    }
+}
+
+pub async fn edit_user_totp_last_used_step(
+    id: &str,
+    step: i64,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE users SET totp_last_used_step = $1
+        WHERE id = $2 AND (totp_last_used_step IS NULL OR totp_last_used_step < $1)
+        ",
+    )
+    .bind(step)
+    .bind(Uuid::from_str(id).unwrap_or_default())
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(ApiError::new(StatusCode::UNAUTHORIZED, "Invalid code.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::internal_server_error())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    use crate::app::test_util;
+
+    use super::*;
+
+    #[sqlx::test(migrations = false)]
+    async fn gets_and_edits_user_by_id(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let id = test_util::insert_user("someone", &state).await;
+        let claims = test_util::access_claims(&id);
+
+        assert_eq!(
+            get_user_by_id(&id.to_string(), &state).await.unwrap().id,
+            id
+        );
+        let e = get_user_by_id("not-a-uuid", &state).await.unwrap_err();
+        assert_eq!(e.code, StatusCode::NOT_FOUND);
+
+        let dto = serde_json::from_value(json!({ "displayname": "Someone Else" })).unwrap();
+        let user = edit_user(&dto, &claims, &state).await.unwrap();
+        assert_eq!(user.displayname, "Someone Else");
+
+        disable_user_totp(&id.to_string(), &state).await.unwrap();
+    }
+
+    async fn usernames(
+        dto: serde_json::Value,
+        claims: &AccessTokenClaims,
+        state: &AppState,
+    ) -> Vec<String> {
+        let dto = serde_json::from_value(dto).unwrap();
+        let mut usernames: Vec<String> = get_users(&dto, claims, state)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|user| user.username)
+            .collect();
+        usernames.sort();
+
+        usernames
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn hides_private_and_blocked_users(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let viewer = test_util::insert_user("viewer", &state).await;
+        test_util::insert_user("public", &state).await;
+        let private = test_util::insert_user("private", &state).await;
+        let blocked = test_util::insert_user("blocked", &state).await;
+        let blocker = test_util::insert_user("blocker", &state).await;
+        sqlx::query("UPDATE users SET private = true WHERE id = $1")
+            .bind(private)
+            .execute(&state.pool)
+            .await
+            .unwrap();
+        for (user_id, blocked_user_id) in [(viewer, blocked), (blocker, viewer)] {
+            sqlx::query(
+                "INSERT INTO blocks (user_id, blocked_user_id, created_at) VALUES ($1, $2, 0)",
+            )
+            .bind(user_id)
+            .bind(blocked_user_id)
+            .execute(&state.pool)
+            .await
+            .unwrap();
+        }
+        let claims = test_util::access_claims(&viewer);
+
+        assert_eq!(
+            usernames(json!({}), &claims, &state).await,
+            ["public", "viewer"]
+        );
+        assert!(
+            usernames(json!({ "id": private.to_string() }), &claims, &state)
+                .await
+                .is_empty()
+        );
+        assert!(usernames(json!({ "search": "block" }), &claims, &state)
+            .await
+            .is_empty());
+        assert!(usernames(json!({ "id": "not-a-uuid" }), &claims, &state)
+            .await
+            .is_empty());
+
+        let private_claims = test_util::access_claims(&private);
+        assert_eq!(
+            usernames(
+                json!({ "id": private.to_string() }),
+                &private_claims,
+                &state
+            )
+            .await,
+            ["private"]
+        );
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn searches_only_discoverable_users(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let viewer = test_util::insert_user("viewer", &state).await;
+        test_util::insert_user("parrot", &state).await;
+        let hidden = test_util::insert_user("parakeet", &state).await;
+        sqlx::query(
+            "UPDATE users SET discoverable_by_username = false, discoverable_by_email = true WHERE id = $1",
+        )
+        .bind(hidden)
+        .execute(&state.pool)
+        .await
+        .unwrap();
+        let claims = test_util::access_claims(&viewer);
+
+        assert_eq!(
+            usernames(json!({ "search": "par" }), &claims, &state).await,
+            ["parrot"]
+        );
+        assert_eq!(
+            usernames(json!({ "search": "Parakeet@Example.com" }), &claims, &state).await,
+            ["parakeet"]
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 
+use crate::users::config::RESERVED_USERNAMES;
+
 pub fn new() -> String {
     let random_string: String = thread_rng()
         .sample_iter(&Alphanumeric)
@@ -9,3 +11,8 @@ pub fn new() -> String {
 
     random_string
 }
+
+pub fn is_reserved(username: &str) -> bool {
+    let username_key = username.to_lowercase();
+    RESERVED_USERNAMES.contains(&username_key.as_str())
+}
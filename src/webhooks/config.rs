@@ -0,0 +1,7 @@
+pub static WEBHOOK_MAX_WEBHOOKS: i64 = 10;
+pub static WEBHOOK_MAX_ATTEMPTS: i32 = 6;
+pub static WEBHOOK_RETRY_BASE: u64 = 30;
+pub static WEBHOOK_TIMEOUT: u64 = 10;
+pub static WEBHOOK_LEASE: u64 = 60;
+pub static WEBHOOK_DELIVERIES_LIMIT: i64 = 100;
+pub static WEBHOOK_DELIVERIES_RETENTION: u64 = 2592000;
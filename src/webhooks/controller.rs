@@ -0,0 +1,88 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use validator::Validate;
+
+use crate::{
+    app::models::api_error::ApiError,
+    auth::{enums::scope::Scope, models::access_token_claims::ExtractClaims},
+    AppState,
+};
+
+use super::{
+    dtos::{create_webhook_dto::CreateWebhookDto, edit_webhook_dto::EditWebhookDto},
+    models::{webhook::Webhook, webhook_delivery::WebhookDelivery},
+    service,
+};
+
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+    Json(dto): Json<CreateWebhookDto>,
+) -> Result<Json<Webhook>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    dto.validate()?;
+    match service::create_webhook(&dto, &claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_webhooks(
+    State(state): State<AppState>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<Json<Vec<Webhook>>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    match service::get_webhooks(&claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn edit_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    ExtractClaims(claims): ExtractClaims,
+    Json(dto): Json<EditWebhookDto>,
+) -> Result<Json<Webhook>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    dto.validate()?;
+    match service::edit_webhook(&id, &dto, &claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<(), ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    service::delete_webhook(&id, &claims, &state).await
+}
+
+pub async fn get_webhook_deliveries(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<Json<Vec<WebhookDelivery>>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    match service::get_webhook_deliveries(&id, &claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn test_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    ExtractClaims(claims): ExtractClaims,
+) -> Result<Json<WebhookDelivery>, ApiError> {
+    claims.require_scope(Scope::ACCOUNT)?;
+    match service::test_webhook(&id, &claims, &state).await {
+        Ok(data) => Ok(Json(data)),
+        Err(e) => Err(e),
+    }
+}
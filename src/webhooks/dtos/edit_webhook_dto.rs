@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct EditWebhookDto {
+    #[validate(url(message = "url must be a valid url."))]
+    #[validate(length(max = 2048, message = "url must be at most 2048 characters."))]
+    pub url: Option<String>,
+    #[validate(custom = "super::validate_webhook_events")]
+    pub events: Option<Vec<String>>,
+    pub enabled: Option<bool>,
+}
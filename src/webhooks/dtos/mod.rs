@@ -0,0 +1,26 @@
+use std::borrow::Cow;
+
+use validator::ValidationError;
+
+use super::enums::webhook_event::WebhookEvent;
+
+pub mod create_webhook_dto;
+pub mod edit_webhook_dto;
+
+pub fn validate_webhook_events(value: &[String]) -> Result<(), ValidationError> {
+    match !value.is_empty()
+        && value
+            .iter()
+            .all(|event| WebhookEvent::ALL.contains(&event.as_str()))
+    {
+        true => Ok(()),
+        false => {
+            let mut error = ValidationError::new("invalid_webhook_events");
+            error.message = Some(Cow::from(format!(
+                "events must contain one or more of {}.",
+                WebhookEvent::ALL.join(", ")
+            )));
+            Err(error)
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod webhook_delivery_status;
+pub mod webhook_event;
@@ -0,0 +1,8 @@
+#[non_exhaustive]
+pub struct WebhookDeliveryStatus;
+
+impl WebhookDeliveryStatus {
+    pub const PENDING: &'static str = "pending";
+    pub const SUCCEEDED: &'static str = "succeeded";
+    pub const FAILED: &'static str = "failed";
+}
@@ -0,0 +1,19 @@
+#[non_exhaustive]
+pub struct WebhookEvent;
+
+impl WebhookEvent {
+    pub const MEMO_CREATED: &'static str = "memo.created";
+    pub const MEMO_UPDATED: &'static str = "memo.updated";
+    pub const MEMO_DELETED: &'static str = "memo.deleted";
+    pub const MEMO_TRIGGERED: &'static str = "memo.triggered";
+    pub const MEMO_COMPLETED: &'static str = "memo.completed";
+    pub const TEST: &'static str = "webhook.test";
+
+    pub const ALL: [&'static str; 5] = [
+        Self::MEMO_CREATED,
+        Self::MEMO_UPDATED,
+        Self::MEMO_DELETED,
+        Self::MEMO_TRIGGERED,
+        Self::MEMO_COMPLETED,
+    ];
+}
@@ -0,0 +1,8 @@
+pub mod config;
+pub mod controller;
+pub mod dtos;
+pub mod enums;
+pub mod models;
+pub mod polo;
+pub mod service;
+pub mod util;
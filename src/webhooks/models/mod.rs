@@ -0,0 +1,3 @@
+pub mod webhook;
+pub mod webhook_delivery;
+pub mod webhook_payload;
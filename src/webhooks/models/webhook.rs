@@ -0,0 +1,49 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    app,
+    webhooks::{dtos::create_webhook_dto::CreateWebhookDto, util::webhook_signature},
+};
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Webhook {
+    pub id: sqlx::types::Uuid,
+    pub user_id: sqlx::types::Uuid,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[sqlx(skip)]
+    pub signing_secret: Option<String>,
+    pub events: Vec<String>,
+    pub enabled: bool,
+    pub updated_at: i64,
+    pub created_at: i64,
+}
+
+impl Webhook {
+    pub fn new(dto: &CreateWebhookDto, user_id: &str) -> Self {
+        let current_time = app::util::time::current_time_in_millis();
+        let secret = webhook_signature::new_secret();
+
+        let mut events = dto.events.clone();
+        events.sort();
+        events.dedup();
+
+        Self {
+            id: Uuid::new_v4(),
+            user_id: Uuid::from_str(user_id).unwrap(),
+            url: dto.url.to_string(),
+            signing_secret: Some(secret.to_string()),
+            secret,
+            events,
+            enabled: true,
+            updated_at: current_time,
+            created_at: current_time,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::{app, webhooks::enums::webhook_delivery_status::WebhookDeliveryStatus};
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: sqlx::types::Uuid,
+    pub webhook_id: sqlx::types::Uuid,
+    pub event: String,
+    #[serde(skip_serializing)]
+    pub payload: String,
+    pub status: String,
+    pub attempts: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<i64>,
+    pub updated_at: i64,
+    pub created_at: i64,
+}
+
+impl WebhookDelivery {
+    pub fn new(id: Uuid, webhook_id: Uuid, event: &str, payload: &str) -> Self {
+        let current_time = app::util::time::current_time_in_millis();
+
+        Self {
+            id,
+            webhook_id,
+            event: event.to_string(),
+            payload: payload.to_string(),
+            status: WebhookDeliveryStatus::PENDING.to_string(),
+            attempts: 0,
+            response_status: None,
+            error: None,
+            next_attempt_at: Some(current_time),
+            updated_at: current_time,
+            created_at: current_time,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    pub id: String,
+    pub event: String,
+    pub created_at: i64,
+    pub data: serde_json::Value,
+}
@@ -0,0 +1,661 @@
+use std::str::FromStr;
+
+use axum::http::StatusCode;
+use serde::Serialize;
+use sqlx::{types::Uuid, Postgres};
+
+use crate::{
+    app::{
+        self,
+        models::{api_error::ApiError, app_state::AppState},
+        util::time,
+    },
+    auth::models::access_token_claims::AccessTokenClaims,
+};
+
+use super::{
+    config::{
+        WEBHOOK_DELIVERIES_LIMIT, WEBHOOK_DELIVERIES_RETENTION, WEBHOOK_LEASE,
+        WEBHOOK_MAX_ATTEMPTS, WEBHOOK_MAX_WEBHOOKS, WEBHOOK_RETRY_BASE,
+    },
+    dtos::{create_webhook_dto::CreateWebhookDto, edit_webhook_dto::EditWebhookDto},
+    enums::{webhook_delivery_status::WebhookDeliveryStatus, webhook_event::WebhookEvent},
+    models::{
+        webhook::Webhook, webhook_delivery::WebhookDelivery, webhook_payload::WebhookPayload,
+    },
+    util::{webhook_signature, webhook_target},
+};
+
+pub async fn create_webhook(
+    dto: &CreateWebhookDto,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<Webhook, ApiError> {
+    check_webhook_url(&dto.url, state).await?;
+
+    let webhook = Webhook::new(dto, &claims.sub);
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO webhooks (id, user_id, url, secret, events, enabled, updated_at, created_at)
+        SELECT $1, $2, $3, $4, $5, $6, $7, $8
+        WHERE (SELECT COUNT(*) FROM webhooks WHERE user_id = $2) < $9
+        ",
+    )
+    .bind(webhook.id)
+    .bind(webhook.user_id)
+    .bind(&webhook.url)
+    .bind(&webhook.secret)
+    .bind(&webhook.events)
+    .bind(webhook.enabled)
+    .bind(webhook.updated_at)
+    .bind(webhook.created_at)
+    .bind(WEBHOOK_MAX_WEBHOOKS)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(webhook),
+            false => Err(ApiError::new(
+                StatusCode::CONFLICT,
+                &format!("You can have at most {} webhooks.", WEBHOOK_MAX_WEBHOOKS),
+            )),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to create webhook.",
+            ))
+        }
+    }
+}
+
+pub async fn get_webhooks(
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<Vec<Webhook>, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, Webhook>(
+        "
+        SELECT * FROM webhooks
+        WHERE user_id = $1
+        ORDER BY created_at DESC
+        ",
+    )
+    .bind(Uuid::from_str(&claims.sub).unwrap_or_default())
+    .fetch_all(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(webhooks) => Ok(webhooks),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get webhooks.",
+            ))
+        }
+    }
+}
+
+pub async fn get_webhook(
+    id: &str,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<Webhook, ApiError> {
+    let Ok(id) = Uuid::from_str(id) else {
+        return Err(ApiError::new(StatusCode::NOT_FOUND, "Webhook not found."));
+    };
+
+    let sqlx_result = sqlx::query_as::<Postgres, Webhook>(
+        "
+        SELECT * FROM webhooks
+        WHERE id = $1 AND user_id = $2
+        ",
+    )
+    .bind(id)
+    .bind(Uuid::from_str(&claims.sub).unwrap_or_default())
+    .fetch_optional(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(data) => match data {
+            Some(webhook) => Ok(webhook),
+            None => Err(ApiError::new(StatusCode::NOT_FOUND, "Webhook not found.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get webhook.",
+            ))
+        }
+    }
+}
+
+pub async fn edit_webhook(
+    id: &str,
+    dto: &EditWebhookDto,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<Webhook, ApiError> {
+    let Ok(id) = Uuid::from_str(id) else {
+        return Err(ApiError::new(StatusCode::NOT_FOUND, "Webhook not found."));
+    };
+
+    if let Some(url) = &dto.url {
+        check_webhook_url(url, state).await?;
+    }
+
+    // SQL
+    let mut query = "UPDATE webhooks SET ".to_string();
+    let mut index: u8 = 0;
+
+    if dto.url.is_some() {
+        index += 1;
+        query.push_str(&format!("url = ${}, ", index));
+    }
+    if dto.events.is_some() {
+        index += 1;
+        query.push_str(&format!("events = ${}, ", index));
+    }
+    if dto.enabled.is_some() {
+        index += 1;
+        query.push_str(&format!("enabled = ${}, ", index));
+    }
+
+    index += 1;
+    query.push_str(&format!("updated_at = ${} ", index));
+    index += 1;
+    query.push_str(&format!("WHERE id = ${} ", index));
+    index += 1;
+    query.push_str(&format!("AND user_id = ${} ", index));
+    query.push_str("RETURNING *");
+
+    // SQLX
+    let mut sqlx = sqlx::query_as::<Postgres, Webhook>(&query);
+
+    if let Some(url) = &dto.url {
+        sqlx = sqlx.bind(url);
+    }
+    if let Some(events) = &dto.events {
+        let mut events = events.clone();
+        events.sort();
+        events.dedup();
+        sqlx = sqlx.bind(events);
+    }
+    if let Some(enabled) = &dto.enabled {
+        sqlx = sqlx.bind(enabled);
+    }
+    sqlx = sqlx.bind(time::current_time_in_millis());
+    sqlx = sqlx.bind(id);
+    sqlx = sqlx.bind(Uuid::from_str(&claims.sub).unwrap_or_default());
+
+    let sqlx_result = sqlx.fetch_optional(&state.pool).await;
+
+    match sqlx_result {
+        Ok(data) => match data {
+            Some(webhook) => Ok(webhook),
+            None => Err(ApiError::new(StatusCode::NOT_FOUND, "Webhook not found.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to edit webhook.",
+            ))
+        }
+    }
+}
+
+pub async fn delete_webhook(
+    id: &str,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let Ok(id) = Uuid::from_str(id) else {
+        return Err(ApiError::new(StatusCode::NOT_FOUND, "Webhook not found."));
+    };
+
+    let sqlx_result = sqlx::query(
+        "
+        DELETE FROM webhooks
+        WHERE id = $1 AND user_id = $2
+        ",
+    )
+    .bind(id)
+    .bind(Uuid::from_str(&claims.sub).unwrap_or_default())
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(result) => match result.rows_affected() > 0 {
+            true => Ok(()),
+            false => Err(ApiError::new(StatusCode::NOT_FOUND, "Webhook not found.")),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to delete webhook.",
+            ))
+        }
+    }
+}
+
+pub async fn get_webhook_deliveries(
+    id: &str,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<Vec<WebhookDelivery>, ApiError> {
+    let webhook = get_webhook(id, claims, state).await?;
+
+    let sqlx_result = sqlx::query_as::<Postgres, WebhookDelivery>(
+        "
+        SELECT * FROM webhook_deliveries
+        WHERE webhook_id = $1
+        ORDER BY created_at DESC
+        LIMIT $2
+        ",
+    )
+    .bind(webhook.id)
+    .bind(WEBHOOK_DELIVERIES_LIMIT)
+    .fetch_all(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(webhook_deliveries) => Ok(webhook_deliveries),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get webhook deliveries.",
+            ))
+        }
+    }
+}
+
+// sends a single attempt right away so the caller sees the receiver's response
+pub async fn test_webhook(
+    id: &str,
+    claims: &AccessTokenClaims,
+    state: &AppState,
+) -> Result<WebhookDelivery, ApiError> {
+    let webhook = get_webhook(id, claims, state).await?;
+    let data = serde_json::json!({ "webhook_id": webhook.id.to_string() });
+
+    let webhook_delivery =
+        create_webhook_delivery(&webhook, WebhookEvent::TEST, data, state).await?;
+    attempt_webhook_delivery(&webhook_delivery.id, state).await?;
+
+    get_webhook_delivery(&webhook_delivery.id, state).await
+}
+
+// queues a delivery for every enabled webhook of the user that subscribed to the event,
+// in the background so memo requests never wait on receivers
+pub fn dispatch<T: Serialize>(user_id: &str, event: &str, data: &T, state: &AppState) {
+    let Ok(data) = serde_json::to_value(data) else {
+        return;
+    };
+    let user_id = Uuid::from_str(user_id).unwrap_or_default();
+    let event = event.to_string();
+    let state = state.clone();
+
+    tokio::spawn(async move {
+        let sqlx_result = sqlx::query_as::<Postgres, Webhook>(
+            "
+            SELECT * FROM webhooks
+            WHERE user_id = $1 AND enabled = true AND $2 = ANY(events)
+            ",
+        )
+        .bind(user_id)
+        .bind(&event)
+        .fetch_all(&state.pool)
+        .await;
+
+        let webhooks = match sqlx_result {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                tracing::error!(%e);
+                return;
+            }
+        };
+
+        for webhook in webhooks {
+            let Ok(webhook_delivery) =
+                create_webhook_delivery(&webhook, &event, data.clone(), &state).await
+            else {
+                continue;
+            };
+
+            let state = state.clone();
+            tokio::spawn(async move {
+                let _ = attempt_webhook_delivery(&webhook_delivery.id, &state).await;
+            });
+        }
+    });
+}
+
+pub async fn retry_webhook_deliveries(state: &AppState) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query_scalar::<Postgres, Uuid>(
+        "
+        SELECT id FROM webhook_deliveries
+        WHERE status = $1 AND next_attempt_at <= $2
+        ORDER BY next_attempt_at ASC
+        LIMIT 100
+        ",
+    )
+    .bind(WebhookDeliveryStatus::PENDING)
+    .bind(time::current_time_in_millis())
+    .fetch_all(&state.pool)
+    .await;
+
+    let ids = match sqlx_result {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::error!(%e);
+            return Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get webhook deliveries.",
+            ));
+        }
+    };
+
+    for id in ids {
+        let _ = attempt_webhook_delivery(&id, state).await;
+    }
+
+    Ok(())
+}
+
+// pending deliveries are kept until they succeed or run out of attempts
+pub async fn delete_old_webhook_deliveries(state: &AppState) -> Result<(), ApiError> {
+    let sqlx_result = sqlx::query(
+        "
+        DELETE FROM webhook_deliveries
+        WHERE status <> $1 AND created_at < $2
+        ",
+    )
+    .bind(WebhookDeliveryStatus::PENDING)
+    .bind(time::current_time_in_millis() - (WEBHOOK_DELIVERIES_RETENTION * 1000) as i64)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to delete webhook deliveries.",
+            ))
+        }
+    }
+}
+
+async fn check_webhook_url(url: &str, state: &AppState) -> Result<(), ApiError> {
+    let allow_private = state.envy.app_env != "production";
+    match webhook_target::is_allowed(url, allow_private).await {
+        true => Ok(()),
+        false => Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "url must be a public https url.",
+        )),
+    }
+}
+
+async fn create_webhook_delivery(
+    webhook: &Webhook,
+    event: &str,
+    data: serde_json::Value,
+    state: &AppState,
+) -> Result<WebhookDelivery, ApiError> {
+    let id = Uuid::new_v4();
+    let payload = WebhookPayload {
+        id: id.to_string(),
+        event: event.to_string(),
+        created_at: time::current_time_in_millis(),
+        data,
+    };
+    let Ok(payload) = serde_json::to_string(&payload) else {
+        return Err(ApiError::internal_server_error());
+    };
+    let webhook_delivery = WebhookDelivery::new(id, webhook.id, event, &payload);
+
+    let sqlx_result = sqlx::query(
+        "
+        INSERT INTO webhook_deliveries (
+            id, webhook_id, event, payload, status, attempts,
+            next_attempt_at, updated_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ",
+    )
+    .bind(webhook_delivery.id)
+    .bind(webhook_delivery.webhook_id)
+    .bind(&webhook_delivery.event)
+    .bind(&webhook_delivery.payload)
+    .bind(&webhook_delivery.status)
+    .bind(webhook_delivery.attempts)
+    .bind(webhook_delivery.next_attempt_at)
+    .bind(webhook_delivery.updated_at)
+    .bind(webhook_delivery.created_at)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(webhook_delivery),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to create webhook delivery.",
+            ))
+        }
+    }
+}
+
+async fn get_webhook_delivery(id: &Uuid, state: &AppState) -> Result<WebhookDelivery, ApiError> {
+    let sqlx_result = sqlx::query_as::<Postgres, WebhookDelivery>(
+        "SELECT * FROM webhook_deliveries WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(data) => match data {
+            Some(webhook_delivery) => Ok(webhook_delivery),
+            None => Err(ApiError::new(
+                StatusCode::NOT_FOUND,
+                "Webhook delivery not found.",
+            )),
+        },
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get webhook delivery.",
+            ))
+        }
+    }
+}
+
+// leases the delivery first so concurrent workers never send the same attempt twice
+async fn attempt_webhook_delivery(id: &Uuid, state: &AppState) -> Result<(), ApiError> {
+    let current_time = time::current_time_in_millis();
+
+    let sqlx_result = sqlx::query_as::<Postgres, (String, String, String, i32)>(
+        "
+        UPDATE webhook_deliveries SET next_attempt_at = $1
+        FROM webhooks
+        WHERE webhook_deliveries.id = $2
+        AND webhook_deliveries.webhook_id = webhooks.id
+        AND webhook_deliveries.status = $3
+        AND webhook_deliveries.next_attempt_at <= $4
+        RETURNING webhooks.url, webhooks.secret, webhook_deliveries.payload,
+            webhook_deliveries.attempts
+        ",
+    )
+    .bind(current_time + (WEBHOOK_LEASE * 1000) as i64)
+    .bind(id)
+    .bind(WebhookDeliveryStatus::PENDING)
+    .bind(current_time)
+    .fetch_optional(&state.pool)
+    .await;
+
+    let (url, secret, payload, attempts) = match sqlx_result {
+        Ok(Some(row)) => row,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            tracing::error!(%e);
+            return Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get webhook delivery.",
+            ));
+        }
+    };
+
+    let (response_status, error) = send_webhook(id, &url, &secret, &payload, state).await;
+    let attempts = attempts + 1;
+    let succeeded = response_status.is_some_and(|status| (200..300).contains(&status));
+
+    let (status, next_attempt_at) = match (succeeded, attempts >= WEBHOOK_MAX_ATTEMPTS) {
+        (true, _) => (WebhookDeliveryStatus::SUCCEEDED, None),
+        (false, true) => (WebhookDeliveryStatus::FAILED, None),
+        (false, false) => {
+            let backoff = WEBHOOK_RETRY_BASE * 2u64.pow(attempts as u32 - 1);
+            let next_attempt_at = time::current_time_in_millis() + (backoff * 1000) as i64;
+            (WebhookDeliveryStatus::PENDING, Some(next_attempt_at))
+        }
+    };
+
+    let sqlx_result = sqlx::query(
+        "
+        UPDATE webhook_deliveries SET
+        status = $1, attempts = $2, response_status = $3, error = $4,
+        next_attempt_at = $5, updated_at = $6
+        WHERE id = $7
+        ",
+    )
+    .bind(status)
+    .bind(attempts)
+    .bind(response_status)
+    .bind(error)
+    .bind(next_attempt_at)
+    .bind(time::current_time_in_millis())
+    .bind(id)
+    .execute(&state.pool)
+    .await;
+
+    match sqlx_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!(%e);
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to edit webhook delivery.",
+            ))
+        }
+    }
+}
+
+async fn send_webhook(
+    id: &Uuid,
+    url: &str,
+    secret: &str,
+    payload: &str,
+    state: &AppState,
+) -> (Option<i32>, Option<String>) {
+    let allow_private = state.envy.app_env != "production";
+    let Some(http_client) = webhook_target::http_client(url, allow_private).await else {
+        return (None, Some("url is not allowed".to_string()));
+    };
+
+    let timestamp = time::current_time_in_secs() as u64;
+    let signature = webhook_signature::sign(secret, timestamp, payload);
+    let event = serde_json::from_str::<WebhookPayload>(payload)
+        .map(|payload| payload.event)
+        .unwrap_or_default();
+
+    let result = http_client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .header(
+            "User-Agent",
+            format!("{}-Webhooks/1.0", app::config::APP_NAME),
+        )
+        .header("X-Perroquet-Delivery", id.to_string())
+        .header("X-Perroquet-Event", event)
+        .header("X-Perroquet-Signature", signature)
+        .body(payload.to_string())
+        .send()
+        .await;
+
+    match result {
+        Ok(res) => {
+            let status = res.status().as_u16() as i32;
+            match res.status().is_success() {
+                true => (Some(status), None),
+                false => (
+                    Some(status),
+                    Some(format!("receiver responded with {}", status)),
+                ),
+            }
+        }
+        Err(e) => (None, Some(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    use crate::app::test_util;
+
+    use super::*;
+
+    #[sqlx::test(migrations = false)]
+    async fn creates_edits_tests_and_deletes_webhook(pool: PgPool) {
+        let state = test_util::app_state(pool).await;
+        let claims = test_util::access_claims(&test_util::insert_user("someone", &state).await);
+        let dto: CreateWebhookDto = serde_json::from_value(json!({
+            "url": "http://127.0.0.1:9/hook",
+            "events": [WebhookEvent::MEMO_CREATED],
+        }))
+        .unwrap();
+
+        let webhook = create_webhook(&dto, &claims, &state).await.unwrap();
+        let id = webhook.id.to_string();
+        assert_eq!(get_webhooks(&claims, &state).await.unwrap().len(), 1);
+
+        let dto: EditWebhookDto = serde_json::from_value(json!({ "enabled": false })).unwrap();
+        let webhook = edit_webhook(&id, &dto, &claims, &state).await.unwrap();
+        assert!(!webhook.enabled);
+
+        let webhook_delivery = test_webhook(&id, &claims, &state).await.unwrap();
+        assert_eq!(webhook_delivery.attempts, 1);
+        assert!(webhook_delivery.error.is_some());
+        let webhook_deliveries = get_webhook_deliveries(&id, &claims, &state).await.unwrap();
+        assert_eq!(webhook_deliveries.len(), 1);
+        assert_eq!(webhook_deliveries[0].id, webhook_delivery.id);
+
+        let e = get_webhook("not-a-uuid", &claims, &state)
+            .await
+            .unwrap_err();
+        assert_eq!(e.code, StatusCode::NOT_FOUND);
+        let e = edit_webhook("not-a-uuid", &dto, &claims, &state)
+            .await
+            .unwrap_err();
+        assert_eq!(e.code, StatusCode::NOT_FOUND);
+        let e = delete_webhook("not-a-uuid", &claims, &state)
+            .await
+            .unwrap_err();
+        assert_eq!(e.code, StatusCode::NOT_FOUND);
+
+        delete_webhook(&id, &claims, &state).await.unwrap();
+        assert!(get_webhooks(&claims, &state).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod webhook_signature;
+pub mod webhook_target;
@@ -0,0 +1,19 @@
+use crate::auth::util::secret_token;
+
+pub static PREFIX: &str = "whsec_";
+
+pub fn new_secret() -> String {
+    format!("{}{}", PREFIX, secret_token::new())
+}
+
+// receivers recompute the hmac over "{t}.{body}" and compare it to v1
+pub fn sign(secret: &str, timestamp: u64, body: &str) -> String {
+    let mac = secret_token::hmac(
+        secret,
+        &[timestamp.to_string().as_bytes(), b".", body.as_bytes()],
+    );
+
+    let signature: String = mac.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    format!("t={},v1={}", timestamp, signature)
+}
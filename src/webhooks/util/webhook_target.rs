@@ -0,0 +1,86 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use reqwest::{redirect::Policy, Url};
+use tokio::net::lookup_host;
+
+use crate::webhooks::config::WEBHOOK_TIMEOUT;
+
+// keeps production webhooks from reaching loopback, private or link-local addresses
+pub async fn is_allowed(url: &str, allow_private: bool) -> bool {
+    resolve(url, allow_private).await.is_some()
+}
+
+// the client is pinned to the addresses that were checked, so the host cannot rebind
+// to an internal address between the check and the request
+pub async fn http_client(url: &str, allow_private: bool) -> Option<reqwest::Client> {
+    let addrs = resolve(url, allow_private).await?;
+
+    let mut builder = reqwest::Client::builder()
+        .redirect(Policy::none())
+        .no_proxy()
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT));
+    if let (Some(host), false) = (Url::parse(url).ok()?.host_str(), addrs.is_empty()) {
+        builder = builder.resolve_to_addrs(host, &addrs);
+    }
+
+    builder.build().ok()
+}
+
+// an empty list means any address is allowed
+async fn resolve(url: &str, allow_private: bool) -> Option<Vec<SocketAddr>> {
+    let url = Url::parse(url).ok()?;
+    match url.scheme() {
+        "https" => {}
+        "http" if allow_private => {}
+        _ => return None,
+    }
+    if allow_private {
+        return Some(Vec::new());
+    }
+
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return None;
+    };
+    let addrs: Vec<SocketAddr> = lookup_host((host, port)).await.ok()?.collect();
+    if addrs.is_empty() || addrs.iter().any(|addr| !is_public(addr.ip())) {
+        return None;
+    }
+
+    Some(addrs)
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let octets = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || octets[0] == 0
+                || octets[0] == 100 && (octets[1] & 0xc0) == 64
+                || octets[0] == 192 && octets[1] == 0 && octets[2] == 0
+                || octets[0] == 198 && (octets[1] & 0xfe) == 18
+                || octets[0] >= 240)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(IpAddr::V4(ip)),
+            None => {
+                let segments = ip.segments();
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || (segments[0] & 0xfe00) == 0xfc00
+                    || (segments[0] & 0xffc0) == 0xfe80
+                    || segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0]
+                    || segments[0] == 0x2002
+                    || segments[0] == 0x2001 && segments[1] == 0x0db8)
+            }
+        },
+    }
+}